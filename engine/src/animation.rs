@@ -0,0 +1,876 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::asset::*;
+use crate::helper::*;
+use crate::world::*;
+
+//================================================================
+
+use hashbrown::HashMap;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//================================================================
+
+/// A data-driven animation state graph, described in a model's meta file.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnimationGraph {
+    /// Name of the initial state.
+    pub start: String,
+    /// Bone set list. Every child of a listed bone is also part of the set.
+    #[serde(default)]
+    pub mask: HashMap<String, Vec<String>>,
+    /// State list.
+    pub state: HashMap<String, AnimationState>,
+    /// Transition list, checked in order.
+    #[serde(default)]
+    pub transition: Vec<AnimationTransition>,
+    /// Layer list, applied in order on top of the active state.
+    #[serde(default)]
+    pub layer: Vec<AnimationLayer>,
+}
+
+impl AnimationGraph {
+    /// Check that every state and mask referenced by the graph exists.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let check_state = |name: &str| {
+            if self.state.contains_key(name) {
+                Ok(())
+            } else {
+                Err(anyhow::Error::msg(format!(
+                    "AnimationGraph::validate(): Could not find state \"{name}\"."
+                )))
+            }
+        };
+
+        check_state(&self.start)?;
+
+        for transition in &self.transition {
+            if let Some(from) = &transition.from {
+                check_state(from)?;
+            }
+
+            check_state(&transition.to)?;
+        }
+
+        for (name, state) in &self.state {
+            if let AnimationMotion::Blend { point, .. } = &state.motion
+                && point.is_empty()
+            {
+                return Err(anyhow::Error::msg(format!(
+                    "AnimationGraph::validate(): Blend state \"{name}\" has no point."
+                )));
+            }
+        }
+
+        for layer in &self.layer {
+            if let Some(mask) = &layer.mask
+                && !self.mask.contains_key(mask)
+            {
+                return Err(anyhow::Error::msg(format!(
+                    "AnimationGraph::validate(): Could not find mask \"{mask}\" for layer \"{}\".",
+                    layer.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that every clip referenced by the graph exists in a clip list, and has at least one
+    /// frame.
+    pub fn validate_clip(&self, animation: &[ModelAnimation]) -> anyhow::Result<()> {
+        for state in self.state.values() {
+            match &state.motion {
+                AnimationMotion::Clip { name } => {
+                    AssetModel::find_animation(animation, name)?;
+                }
+                AnimationMotion::Blend { point, .. } => {
+                    for point in point {
+                        AssetModel::find_animation(animation, &point.clip)?;
+                    }
+                }
+            }
+        }
+
+        for layer in &self.layer {
+            AssetModel::find_animation(animation, &layer.clip)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_state(&self, name: &str) -> anyhow::Result<&AnimationState> {
+        self.state.get(name).ok_or(anyhow::Error::msg(format!(
+            "AnimationGraph::get_state(): Could not find state \"{name}\"."
+        )))
+    }
+
+    /// Get the bone mask for a bone set, with one entry per bone in the model's skeleton.
+    fn get_mask(&self, model: &AssetModel, name: &str) -> anyhow::Result<Vec<bool>> {
+        let list = self.mask.get(name).ok_or(anyhow::Error::msg(format!(
+            "AnimationGraph::get_mask(): Could not find mask \"{name}\"."
+        )))?;
+        let animation = model.animation.first().ok_or(anyhow::Error::msg(
+            "AnimationGraph::get_mask(): Model has no animation.",
+        ))?;
+        let bone =
+            unsafe { std::slice::from_raw_parts(animation.bones, animation.boneCount as usize) };
+
+        let mut mask: Vec<bool> = bone
+            .iter()
+            .map(|bone| list.contains(&name_from_raw(&bone.name)))
+            .collect();
+
+        // bones are not guaranteed to be sorted by hierarchy, so spread until nothing changes.
+        let mut change = true;
+
+        while change {
+            change = false;
+
+            for (i, bone) in bone.iter().enumerate() {
+                if !mask[i] && bone.parent >= 0 && mask[bone.parent as usize] {
+                    mask[i] = true;
+                    change = true;
+                }
+            }
+        }
+
+        Ok(mask)
+    }
+}
+
+/// A state in the animation graph.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnimationState {
+    /// Clip (or clips) to play.
+    pub motion: AnimationMotion,
+    /// Play-back rate, in frames per second.
+    pub rate: f32,
+    /// Restart the state once the last frame has been reached.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+/// Every possible motion for an animation state.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum AnimationMotion {
+    /// A single clip.
+    Clip { name: String },
+    /// A blend between clips, driven by a parameter. Point list must be sorted by value.
+    Blend {
+        parameter: String,
+        point: Vec<BlendPoint>,
+    },
+}
+
+impl AnimationMotion {
+    /// Get the clip (or pair of clips) to sample, with the weight of the second clip.
+    fn get_clip(&self, parameter: &HashMap<String, f32>) -> (&str, Option<(&str, f32)>) {
+        match self {
+            Self::Clip { name } => (name, None),
+            Self::Blend {
+                parameter: name,
+                point,
+            } => {
+                let value = parameter.get(name).copied().unwrap_or_default();
+
+                for pair in point.windows(2) {
+                    let (a, b) = (&pair[0], &pair[1]);
+
+                    if value < b.value {
+                        if value <= a.value {
+                            return (&a.clip, None);
+                        }
+
+                        let weight = (value - a.value) / (b.value - a.value);
+
+                        return (&a.clip, Some((&b.clip, weight)));
+                    }
+                }
+
+                // value is past the last point (or there is only one point).
+                (&point[point.len() - 1].clip, None)
+            }
+        }
+    }
+}
+
+/// A point in a blend state.
+#[derive(Debug, Deserialize, Clone)]
+pub struct BlendPoint {
+    /// Parameter value at which the clip is at full weight.
+    pub value: f32,
+    /// Clip name.
+    pub clip: String,
+}
+
+/// A transition between two states.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnimationTransition {
+    /// Source state. None if the transition can start from any state.
+    pub from: Option<String>,
+    /// Target state.
+    pub to: String,
+    /// Cross-fade time, in seconds.
+    pub time: f32,
+    /// Condition list. Every condition must pass for the transition to start.
+    #[serde(default)]
+    pub condition: Vec<AnimationCondition>,
+}
+
+/// Every possible condition for a transition.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum AnimationCondition {
+    /// Parameter is greater than a value.
+    Greater { parameter: String, value: f32 },
+    /// Parameter is lesser than a value.
+    Lesser { parameter: String, value: f32 },
+    /// The source state is not repeating, and has reached its last frame.
+    Finish,
+}
+
+/// An animation layer, played on top of the active state.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnimationLayer {
+    /// Layer name.
+    pub name: String,
+    /// Clip name.
+    pub clip: String,
+    /// Play-back rate, in frames per second.
+    pub rate: f32,
+    /// Restart the layer once the last frame has been reached.
+    #[serde(default)]
+    pub repeat: bool,
+    /// Bone set the layer is restricted to. None if the layer affects every bone.
+    pub mask: Option<String>,
+    /// Layer mode.
+    #[serde(default)]
+    pub mode: LayerMode,
+}
+
+/// Every possible mode for an animation layer.
+#[derive(Debug, Deserialize, Default, Copy, Clone, PartialEq)]
+pub enum LayerMode {
+    /// Layer replaces the pose of the state.
+    #[default]
+    Override,
+    /// Layer is added to the pose of the state, relative to the first frame of the layer's clip.
+    Additive,
+}
+
+//================================================================
+
+#[derive(Copy, Clone)]
+struct PoseBone {
+    point: Vector3,
+    angle: Quaternion,
+    scale: Vector3,
+}
+
+impl PoseBone {
+    fn blend(&self, other: &Self, weight: f32) -> Self {
+        Self {
+            point: self.point.lerp(other.point, weight),
+            angle: self.angle.slerp(other.angle, weight),
+            scale: self.scale.lerp(other.scale, weight),
+        }
+    }
+
+    /// Convert a model-space bone to a parent-space bone.
+    fn to_local(self, parent: &Self) -> Self {
+        let angle = parent.angle.inverted();
+
+        Self {
+            point: (self.point - parent.point).rotate_by(angle),
+            angle: angle * self.angle,
+            scale: self.scale / parent.scale,
+        }
+    }
+
+    /// Convert a parent-space bone to a model-space bone, the same way raylib builds its frame
+    /// poses.
+    fn to_model(self, parent: &Self) -> Self {
+        Self {
+            point: self.point.rotate_by(parent.angle) + parent.point,
+            angle: parent.angle * self.angle,
+            scale: self.scale * parent.scale,
+        }
+    }
+}
+
+/// A skeleton pose, with one parent-space transform per bone. Blending is done in parent space,
+/// so that every bone keeps its length and stays attached to its parent.
+#[derive(Clone, Default)]
+pub struct Pose {
+    bone: Vec<PoseBone>,
+    /// Parent index of each bone, or -1 for a root bone.
+    parent: Vec<i32>,
+}
+
+impl Pose {
+    /// Sample a clip at a given (fractional) frame, interpolating between the two closest frames.
+    /// A frame past the last one blends from the last frame back to the first, for a repeating
+    /// clip. An empty clip will give back an empty pose.
+    pub fn sample(animation: &ModelAnimation, frame: f32) -> Self {
+        let count = animation.frameCount;
+
+        if count <= 0 || animation.framePoses.is_null() || animation.bones.is_null() {
+            return Self::default();
+        }
+
+        let frame = frame.clamp(0.0, count as f32);
+        let weight = frame.fract();
+        let frame_a = frame.floor() as i32 % count;
+        let frame_b = (frame_a + 1) % count;
+
+        let model: Vec<PoseBone> = (0..animation.boneCount as usize)
+            .map(|i| {
+                let a = Self::bone_at(animation, frame_a, i);
+                let b = Self::bone_at(animation, frame_b, i);
+
+                a.blend(&b, weight)
+            })
+            .collect();
+        let parent: Vec<i32> =
+            unsafe { std::slice::from_raw_parts(animation.bones, animation.boneCount as usize) }
+                .iter()
+                .map(|bone| bone.parent)
+                .collect();
+
+        let bone = model
+            .iter()
+            .enumerate()
+            .map(|(i, bone)| match Self::parent_index(&parent, i) {
+                Some(index) => bone.to_local(&model[index]),
+                None => *bone,
+            })
+            .collect();
+
+        Self { bone, parent }
+    }
+
+    /// Blend toward another pose. If a mask is given, only bones in the mask are blended.
+    pub fn blend(&mut self, other: &Pose, weight: f32, mask: Option<&[bool]>) {
+        for (i, (bone, other)) in self.bone.iter_mut().zip(&other.bone).enumerate() {
+            if mask.is_none_or(|mask| mask.get(i).copied().unwrap_or_default()) {
+                *bone = bone.blend(other, weight);
+            }
+        }
+    }
+
+    /// Add the difference between a pose and its reference pose on top of this pose. If a mask is
+    /// given, only bones in the mask are added.
+    pub fn add(&mut self, other: &Pose, reference: &Pose, weight: f32, mask: Option<&[bool]>) {
+        for (i, ((bone, other), reference)) in self
+            .bone
+            .iter_mut()
+            .zip(&other.bone)
+            .zip(&reference.bone)
+            .enumerate()
+        {
+            if mask.is_none_or(|mask| mask.get(i).copied().unwrap_or_default()) {
+                let angle = reference.angle.inverted() * other.angle;
+                let scale = other.scale / reference.scale;
+
+                bone.point += (other.point - reference.point) * weight;
+                bone.angle = bone.angle * Quaternion::identity().slerp(angle, weight);
+                bone.scale = bone.scale * Vector3::one().lerp(scale, weight);
+            }
+        }
+    }
+
+    /// Pose the model. The pose is shared by every instance of the model, so this must be called
+    /// right before drawing each instance.
+    pub fn apply(&self, model: &AssetModel) -> anyhow::Result<()> {
+        let source = model
+            .animation
+            .first()
+            .ok_or(anyhow::Error::msg("Pose::apply(): Model has no animation."))?;
+
+        if self.bone.is_empty() || self.bone.len() != source.boneCount as usize {
+            return Err(anyhow::Error::msg(format!(
+                "Pose::apply(): Pose has {} bone(s), model has {}.",
+                self.bone.len(),
+                source.boneCount
+            )));
+        }
+
+        let mut transform: Vec<ffi::Transform> = self
+            .get_model_space()
+            .iter()
+            .map(|bone| ffi::Transform {
+                translation: bone.point.into(),
+                rotation: bone.angle.into(),
+                scale: bone.scale.into(),
+            })
+            .collect();
+        let mut frame = transform.as_mut_ptr();
+
+        // a scratch, single-frame animation, sharing the skeleton of the source animation.
+        let scratch = ffi::ModelAnimation {
+            boneCount: transform.len() as i32,
+            frameCount: 1,
+            bones: source.bones,
+            framePoses: &mut frame,
+            name: source.name,
+        };
+
+        unsafe {
+            ffi::UpdateModelAnimation(*model.model, scratch, 0);
+        }
+
+        Ok(())
+    }
+
+    /// Get the model-space point, angle and scale of a bone.
+    pub fn get_bone(&self, index: usize) -> Option<(Vector3, Quaternion, Vector3)> {
+        if index >= self.bone.len() {
+            return None;
+        }
+
+        let bone = self.get_model_space()[index];
+
+        Some((bone.point, bone.angle, bone.scale))
+    }
+
+    /// Get every bone in model space.
+    fn get_model_space(&self) -> Vec<PoseBone> {
+        let mut model = vec![None; self.bone.len()];
+
+        (0..self.bone.len())
+            .map(|i| self.resolve_bone(i, &mut model, 0))
+            .collect()
+    }
+
+    /// Get a bone in model space, resolving (and caching) every parent first. Bones are not
+    /// guaranteed to be sorted by hierarchy.
+    fn resolve_bone(&self, index: usize, model: &mut [Option<PoseBone>], depth: usize) -> PoseBone {
+        if let Some(bone) = model[index] {
+            return bone;
+        }
+
+        let local = self.bone[index];
+        let bone = match Self::parent_index(&self.parent, index) {
+            // a malformed skeleton with a cycle in it is treated as a root.
+            Some(parent) if depth < self.bone.len() => {
+                local.to_model(&self.resolve_bone(parent, model, depth + 1))
+            }
+            _ => local,
+        };
+
+        model[index] = Some(bone);
+
+        bone
+    }
+
+    fn parent_index(parent: &[i32], index: usize) -> Option<usize> {
+        let value = *parent.get(index)?;
+
+        if value >= 0 && (value as usize) < parent.len() && value as usize != index {
+            Some(value as usize)
+        } else {
+            None
+        }
+    }
+
+    fn bone_at(animation: &ModelAnimation, frame: i32, bone: usize) -> PoseBone {
+        let transform = unsafe { *(*animation.framePoses.add(frame as usize)).add(bone) };
+
+        PoseBone {
+            point: transform.translation.into(),
+            angle: transform.rotation.into(),
+            scale: transform.scale.into(),
+        }
+    }
+}
+
+//================================================================
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct AnimationTrack {
+    /// State name.
+    state: String,
+    /// Normalized play-back position, from 0.0 to 1.0.
+    phase: f32,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct FadeTrack {
+    /// Previous state.
+    track: AnimationTrack,
+    /// Time since the cross-fade started, in seconds.
+    time: f32,
+    /// Cross-fade length, in seconds.
+    length: f32,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+struct LayerTrack {
+    /// Normalized play-back position, from 0.0 to 1.0.
+    phase: f32,
+    /// Layer weight.
+    weight: f32,
+}
+
+/// Per-instance animation state graph play-back.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Animator {
+    /// Parameter list, used by blend states and transition conditions.
+    pub parameter: HashMap<String, f32>,
+    /// Active state.
+    track: AnimationTrack,
+    /// Previous state, while a cross-fade is in progress.
+    fade: Option<FadeTrack>,
+    /// Active layer list.
+    layer: HashMap<String, LayerTrack>,
+}
+
+impl Animator {
+    /// Create a new animator, at the initial state of the model's animation graph.
+    pub fn new(model: &AssetModel) -> anyhow::Result<Self> {
+        let graph = Self::get_graph(model)?;
+
+        Ok(Self {
+            parameter: HashMap::default(),
+            track: AnimationTrack {
+                state: graph.start.clone(),
+                phase: 0.0,
+            },
+            fade: None,
+            layer: HashMap::default(),
+        })
+    }
+
+    /// Get the name of the active state.
+    pub fn get_state(&self) -> &str {
+        &self.track.state
+    }
+
+    /// Set a parameter.
+    pub fn set_parameter(&mut self, name: &str, value: f32) {
+        self.parameter.insert(name.to_string(), value);
+    }
+
+    /// Get a parameter. Missing parameters are zero.
+    pub fn get_parameter(&self, name: &str) -> f32 {
+        self.parameter.get(name).copied().unwrap_or_default()
+    }
+
+    /// Play a layer from the start, at a given weight.
+    pub fn play_layer(&mut self, name: &str, weight: f32) {
+        self.layer
+            .insert(name.to_string(), LayerTrack { phase: 0.0, weight });
+    }
+
+    /// Set the weight of an active layer.
+    pub fn set_layer_weight(&mut self, name: &str, weight: f32) {
+        if let Some(layer) = self.layer.get_mut(name) {
+            layer.weight = weight;
+        }
+    }
+
+    /// Stop a layer.
+    pub fn stop_layer(&mut self, name: &str) {
+        self.layer.remove(name);
+    }
+
+    /// Advance the animator by one game tick: check every transition, then advance the active
+    /// state, the cross-fade and every layer. Events fire for the active state's dominant clip.
+    pub fn update(
+        &mut self,
+        app: &App,
+        world: &mut World,
+        path: &str,
+        point: Vector3,
+        owner: usize,
+    ) -> anyhow::Result<()> {
+        let model = world.scene.asset.get_model(path)?;
+        let graph = Self::get_graph(model)?;
+
+        for transition in &graph.transition {
+            if self.check_transition(graph, transition)? {
+                let track = std::mem::replace(
+                    &mut self.track,
+                    AnimationTrack {
+                        state: transition.to.clone(),
+                        phase: 0.0,
+                    },
+                );
+
+                self.fade = Some(FadeTrack {
+                    track,
+                    time: 0.0,
+                    length: transition.time,
+                });
+
+                break;
+            }
+        }
+
+        let event_list = Self::advance(model, graph, &self.parameter, &mut self.track)?;
+
+        if let Some(fade) = &mut self.fade {
+            Self::advance(model, graph, &self.parameter, &mut fade.track)?;
+
+            fade.time += World::TIME_STEP;
+
+            if fade.time >= fade.length {
+                self.fade = None;
+            }
+        }
+
+        for layer in &graph.layer {
+            if let Some(track) = self.layer.get_mut(&layer.name) {
+                let count = model.get_animation(&layer.clip)?.frameCount;
+
+                track.phase += World::TIME_STEP * layer.rate / count as f32;
+
+                if layer.repeat {
+                    track.phase %= 1.0;
+                }
+            }
+        }
+
+        // non-repeating layers are removed once they reach their last frame.
+        self.layer.retain(|name, track| {
+            track.phase < 1.0
+                || graph
+                    .layer
+                    .iter()
+                    .any(|layer| layer.name == *name && layer.repeat)
+        });
+
+        Animation::event_fire(app, world, event_list, point, owner)
+    }
+
+    /// Get the model-space point, angle and scale of a bone in the blended pose. None if the
+    /// model has no such bone.
+    pub fn get_bone_data(
+        &self,
+        model: &AssetModel,
+        bone_name: &str,
+    ) -> anyhow::Result<Option<(Vector3, Quaternion, Vector3)>> {
+        if let Some(bone) = model.get_bone(bone_name) {
+            return Ok(self.get_pose(model)?.get_bone(bone));
+        }
+
+        Ok(None)
+    }
+
+    /// Get the blended pose of the active state, cross-fade and every layer.
+    pub fn get_pose(&self, model: &AssetModel) -> anyhow::Result<Pose> {
+        let graph = Self::get_graph(model)?;
+        let mut pose = Self::track_pose(model, graph, &self.parameter, &self.track)?;
+
+        if let Some(fade) = &self.fade {
+            let mut from = Self::track_pose(model, graph, &self.parameter, &fade.track)?;
+            let weight = if fade.length > 0.0 {
+                (fade.time / fade.length).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+
+            from.blend(&pose, weight, None);
+            pose = from;
+        }
+
+        for layer in &graph.layer {
+            if let Some(track) = self.layer.get(&layer.name) {
+                let animation = model.get_animation(&layer.clip)?;
+                let frame = Self::phase_frame(track.phase, animation.frameCount, layer.repeat);
+                let other = Pose::sample(animation, frame);
+                let mask = match &layer.mask {
+                    Some(mask) => Some(graph.get_mask(model, mask)?),
+                    None => None,
+                };
+
+                match layer.mode {
+                    LayerMode::Override => pose.blend(&other, track.weight, mask.as_deref()),
+                    LayerMode::Additive => pose.add(
+                        &other,
+                        &Pose::sample(animation, 0.0),
+                        track.weight,
+                        mask.as_deref(),
+                    ),
+                }
+            }
+        }
+
+        Ok(pose)
+    }
+
+    fn get_graph(model: &AssetModel) -> anyhow::Result<&AnimationGraph> {
+        model.graph.as_ref().ok_or(anyhow::Error::msg(
+            "Animator::get_graph(): Model has no animation graph.",
+        ))
+    }
+
+    fn check_transition(
+        &self,
+        graph: &AnimationGraph,
+        transition: &AnimationTransition,
+    ) -> anyhow::Result<bool> {
+        if transition.to == self.track.state {
+            return Ok(false);
+        }
+
+        if let Some(from) = &transition.from
+            && *from != self.track.state
+        {
+            return Ok(false);
+        }
+
+        let state = graph.get_state(&self.track.state)?;
+
+        for condition in &transition.condition {
+            let pass = match condition {
+                AnimationCondition::Greater { parameter, value } => {
+                    self.get_parameter(parameter) > *value
+                }
+                AnimationCondition::Lesser { parameter, value } => {
+                    self.get_parameter(parameter) < *value
+                }
+                AnimationCondition::Finish => !state.repeat && self.track.phase >= 1.0,
+            };
+
+            if !pass {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Advance a track by one game tick, and get every event crossed by the dominant clip.
+    fn advance(
+        model: &AssetModel,
+        graph: &AnimationGraph,
+        parameter: &HashMap<String, f32>,
+        track: &mut AnimationTrack,
+    ) -> anyhow::Result<Vec<AnimationEvent>> {
+        let state = graph.get_state(&track.state)?;
+        let (clip_a, clip_b) = state.motion.get_clip(parameter);
+        let count_a = model.get_animation(clip_a)?.frameCount;
+
+        // blended clips are synchronized by phase, with a length weighted between both clips.
+        let (clip, count, length) = if let Some((clip_b, weight)) = clip_b {
+            let count_b = model.get_animation(clip_b)?.frameCount;
+            let length = interpolate(count_a as f32, count_b as f32, weight);
+
+            if weight > 0.5 {
+                (clip_b, count_b, length)
+            } else {
+                (clip_a, count_a, length)
+            }
+        } else {
+            (clip_a, count_a, count_a as f32)
+        };
+
+        let phase = track.phase + World::TIME_STEP * state.rate / length;
+        let event_list = Animation::event_cross(
+            model,
+            clip,
+            Self::phase_frame(track.phase, count, state.repeat),
+            Self::phase_frame(phase, count, state.repeat),
+            count,
+            state.repeat,
+        );
+
+        track.phase = if state.repeat {
+            phase % 1.0
+        } else {
+            phase.min(1.0)
+        };
+
+        Ok(event_list)
+    }
+
+    fn track_pose(
+        model: &AssetModel,
+        graph: &AnimationGraph,
+        parameter: &HashMap<String, f32>,
+        track: &AnimationTrack,
+    ) -> anyhow::Result<Pose> {
+        let state = graph.get_state(&track.state)?;
+        let (clip_a, clip_b) = state.motion.get_clip(parameter);
+        let animation = model.get_animation(clip_a)?;
+        let mut pose = Pose::sample(
+            animation,
+            Self::phase_frame(track.phase, animation.frameCount, state.repeat),
+        );
+
+        if let Some((clip_b, weight)) = clip_b {
+            let animation = model.get_animation(clip_b)?;
+            let other = Pose::sample(
+                animation,
+                Self::phase_frame(track.phase, animation.frameCount, state.repeat),
+            );
+
+            pose.blend(&other, weight, None);
+        }
+
+        Ok(pose)
+    }
+
+    /// Convert a normalized play-back position to a frame. Repeating clips wrap from the last
+    /// frame back to the first, non-repeating clips end on the last frame.
+    fn phase_frame(phase: f32, count: i32, repeat: bool) -> f32 {
+        if repeat {
+            phase * count as f32
+        } else {
+            phase * (count - 1).max(0) as f32
+        }
+    }
+}
@@ -49,6 +49,8 @@
 */
 
 use crate::helper::*;
+use crate::record::*;
+use crate::stat::*;
 use crate::user::*;
 use crate::view::*;
 use crate::world::*;
@@ -56,6 +58,7 @@ use crate::world::*;
 //================================================================
 
 use raylib::prelude::*;
+use std::time::Instant;
 
 //================================================================
 
@@ -70,6 +73,10 @@ pub struct App<'a> {
     pub view: View<'a>,
     /// User configuration.
     pub user: User,
+    /// Screenshot and frame sequence recording.
+    pub record: Record,
+    /// Render statistics.
+    pub stat: RenderStat,
 }
 
 impl<'a> App<'a> {
@@ -126,13 +133,58 @@ impl<'a> App<'a> {
                     world.main(&mut *app, &mut draw, &mut *ctx)?;
                 }
 
+                let time = Instant::now();
+
                 View::draw_layout(&mut *app, &mut draw, &mut *ctx)?;
+
+                (*app).stat.add_time(StatTime::Interface, time);
+                (*app)
+                    .stat
+                    .end((*app).world.as_ref(), draw.get_frame_time());
+
+                if (*app).user.debug.draw_stat {
+                    (*app).stat.draw(&mut draw);
+                }
+
+                (*app).user.input_screenshot.poll(&draw);
+
+                if (*app).user.input_screenshot.get_press() {
+                    (*app).record.screenshot();
+                }
+
+                (*app).user.input_screenshot.wipe();
+
+                let scene = match &(*app).world {
+                    Some(world) => &world.scene,
+                    None => &(*app).view.scene,
+                };
+
+                // a failed write must not take the game down, only the capture.
+                if let Err(error) = (*app).record.draw(&mut draw, &(*ctx).thread, scene) {
+                    (*app)
+                        .view
+                        .logger
+                        .print_failure(&format!("Error: {error:?}"));
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Create an app with fixed user data, for off-screen captures. The user file is neither
+    /// read nor written.
+    pub fn new_fixed() -> Self {
+        Self {
+            close: false,
+            world: None,
+            view: View::default(),
+            user: User::new_fixed(),
+            record: Record::default(),
+            stat: RenderStat::default(),
+        }
+    }
+
     /// Initialize the app proper after context is ready.
     pub fn initialize(&mut self, context: &mut Context) -> anyhow::Result<()> {
         let app = { self as *mut Self };
@@ -200,6 +252,29 @@ impl Context {
         })
     }
 
+    /// Create a new context with a hidden window, for off-screen rendering. With a software GL
+    /// driver (i.e. "LIBGL_ALWAYS_SOFTWARE=1"), no GPU is needed.
+    pub fn new_hidden(width: i32, height: i32) -> anyhow::Result<Self> {
+        unsafe {
+            ffi::SetConfigFlags(ConfigFlags::FLAG_WINDOW_HIDDEN as u32);
+        }
+
+        let (mut handle, thread) = raylib::init()
+            .size(width, height)
+            .title("Gauntlet Complex")
+            .build();
+
+        handle.set_exit_key(None);
+
+        let audio = RaylibAudio::init_audio_device()?;
+
+        Ok(Self {
+            handle,
+            thread,
+            audio,
+        })
+    }
+
     pub fn toggle_full_screen(&mut self) {
         let i = get_current_monitor();
         self.handle
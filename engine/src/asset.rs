@@ -48,7 +48,10 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::animation::*;
 use crate::app::*;
+use crate::helper::*;
+use crate::shader::*;
 
 //================================================================
 
@@ -72,7 +75,9 @@ pub struct Asset<'a> {
     /// Music hash-map.
     music: HashMap<String, Music<'a>>,
     /// Font hash-map.
-    font: HashMap<String, Font>,
+    font: HashMap<String, AssetFont>,
+    /// Atlas hash-map.
+    atlas: HashMap<String, AssetAtlas>,
 }
 
 impl<'a> Asset<'a> {
@@ -91,13 +96,25 @@ impl<'a> Asset<'a> {
             .handle
             .load_model_animations(&context.thread, name)
             .unwrap_or_default();
-        let meta = ModelMeta::new(name)?;
+        let mut meta = ModelMeta::new(name)?;
+        let graph = meta.graph.take();
+        let level = meta.level.take().unwrap_or_default();
+        let fade = meta.fade.take();
+
+        if let Some(graph) = &graph {
+            graph.validate()?;
+            graph.validate_clip(&animation)?;
+        }
+
+        ModelLevel::validate(&level, fade.as_ref()).map_err(|error| {
+            anyhow::Error::msg(format!("Asset::set_model(): Model \"{name}\": {error}"))
+        })?;
 
         if let Some(texture) = &meta.texture {
             let material = model.materials_mut();
 
             for (i, path) in texture.iter().enumerate() {
-                let texture = self.set_texture(context, &format!("data/{path}"))?;
+                let texture = self.set_texture(context, &ModelMeta::get_data_path(path))?;
 
                 material[i + 1].maps_mut()[MaterialMapIndex::MATERIAL_MAP_ALBEDO as usize]
                     .texture = **texture;
@@ -105,12 +122,61 @@ impl<'a> Asset<'a> {
             }
         }
 
+        let mut material_list = vec![ModelMaterial::default(); model.materials().len()];
+
+        if let Some(material_map) = &meta.material {
+            let material = model.materials_mut();
+            let mut visit: HashMap<usize, &String> = HashMap::default();
+
+            for (material_name, entry) in material_map {
+                let Some(slot) = material.get_mut(entry.index) else {
+                    return Err(anyhow::Error::msg(format!(
+                        "Asset::set_model(): Material \"{material_name}\" has an invalid index {} for model \"{name}\".",
+                        entry.index
+                    )));
+                };
+
+                if let Some(other) = visit.insert(entry.index, material_name) {
+                    return Err(anyhow::Error::msg(format!(
+                        "Asset::set_model(): Material \"{material_name}\" and \"{other}\" share index {} for model \"{name}\".",
+                        entry.index
+                    )));
+                }
+
+                let map_list = [
+                    (&entry.albedo, MaterialMapIndex::MATERIAL_MAP_ALBEDO),
+                    (&entry.normal, MaterialMapIndex::MATERIAL_MAP_NORMAL),
+                    (&entry.emissive, MaterialMapIndex::MATERIAL_MAP_EMISSION),
+                    (&entry.roughness, MaterialMapIndex::MATERIAL_MAP_ROUGHNESS),
+                ];
+
+                for (path, map) in map_list {
+                    if let Some(path) = path {
+                        let texture = self.set_texture(context, &ModelMeta::get_data_path(path))?;
+                        slot.maps_mut()[map as usize].texture = **texture;
+                    }
+                }
+
+                slot.maps_mut()[MaterialMapIndex::MATERIAL_MAP_ALBEDO as usize].color =
+                    entry.tint.into();
+
+                material_list[entry.index] = entry.clone();
+            }
+        }
+
+        let bound = model.get_model_bounding_box();
+
         self.model.insert(
             name.to_string(),
             AssetModel {
                 model,
+                bound,
+                level,
+                fade,
                 animation,
+                material: material_list,
                 event: ModelEvent::from_model_meta(meta),
+                graph,
             },
         );
 
@@ -183,6 +249,39 @@ impl<'a> Asset<'a> {
         self.get_shader(name)
     }
 
+    /// Create a shader permutation. Each source file is pre-processed, and the resulting shader is cached under its permutation name.
+    pub fn set_shader_define(
+        &mut self,
+        context: &mut Context,
+        name: &str,
+        path_vs: Option<&str>,
+        path_fs: Option<&str>,
+        define: &ShaderDefine,
+    ) -> anyhow::Result<&mut Shader> {
+        let name = define.name(name);
+
+        if self.has_shader(&name) {
+            return self.get_shader(&name);
+        }
+
+        let source_vs = path_vs
+            .map(|path| ShaderSource::new(path, define))
+            .transpose()?;
+        let source_fs = path_fs
+            .map(|path| ShaderSource::new(path, define))
+            .transpose()?;
+
+        let shader = context.handle.load_shader_from_memory(
+            &context.thread,
+            source_vs.as_deref(),
+            source_fs.as_deref(),
+        );
+
+        self.shader.insert(name.clone(), shader);
+
+        self.get_shader(&name)
+    }
+
     /// Get a shader.
     pub fn get_shader(&mut self, name: &str) -> anyhow::Result<&mut Shader> {
         self.shader.get_mut(name).ok_or(anyhow::Error::msg(format!(
@@ -267,28 +366,40 @@ impl<'a> Asset<'a> {
 
     //================================================================
 
-    /// Create a font.
+    /// Create a font, baking every printable ASCII code point and every code point in a given
+    /// string. A font already in the resource map is baked again if the code point string changed.
     pub fn set_font(
         &mut self,
         context: &mut Context,
         name: &str,
         size: i32,
-    ) -> anyhow::Result<&Font> {
-        if self.has_font(name) {
+        code: &str,
+    ) -> anyhow::Result<&AssetFont> {
+        let mut list: Vec<char> = (' '..='~')
+            .chain(code.chars().filter(|c| !c.is_control()))
+            .collect();
+        list.sort();
+        list.dedup();
+        let request: String = list.into_iter().collect();
+
+        if let Some(font) = self.font.get(name)
+            && font.request == request
+        {
             return self.get_font(name);
         }
 
         let font = context
             .handle
-            .load_font_ex(&context.thread, name, size, None)?;
+            .load_font_ex(&context.thread, name, size, Some(&request))?;
 
-        self.font.insert(name.to_string(), font);
+        self.font
+            .insert(name.to_string(), AssetFont::new(font, request));
 
         self.get_font(name)
     }
 
     /// Get a font.
-    pub fn get_font(&self, name: &str) -> anyhow::Result<&Font> {
+    pub fn get_font(&self, name: &str) -> anyhow::Result<&AssetFont> {
         self.font.get(name).ok_or(anyhow::Error::msg(format!(
             "Asset::get_font(): Could not find asset \"{name}\"."
         )))
@@ -298,6 +409,174 @@ impl<'a> Asset<'a> {
     pub fn has_font(&self, name: &str) -> bool {
         self.font.contains_key(name)
     }
+
+    //================================================================
+
+    /// Create an atlas. The atlas texture is kept in the texture hash-map.
+    ///
+    /// For data made before atlas files, a missing "{name}.json" atlas file falls back to a
+    /// "{name}" folder of loose image files, and an image file path is loaded as an atlas of its
+    /// own. Each image file is a frame, named after the file stem, with its own texture.
+    pub fn set_atlas(&mut self, context: &mut Context, name: &str) -> anyhow::Result<&AssetAtlas> {
+        if self.has_atlas(name) {
+            return self.get_atlas(name);
+        }
+
+        let path = std::path::Path::new(name);
+        let folder = std::path::Path::new(name.strip_suffix(".json").unwrap_or(name));
+
+        let atlas = if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+        {
+            let meta: AtlasMeta = serde_json::from_str(&std::fs::read_to_string(name)?)?;
+            let texture = ModelMeta::get_data_path(&meta.texture);
+
+            self.set_texture(context, &texture)?;
+
+            AssetAtlas {
+                frame: meta
+                    .frame
+                    .into_iter()
+                    .map(|(key, frame)| {
+                        (
+                            key,
+                            AtlasShape {
+                                texture: texture.clone(),
+                                shape: Rectangle::new(frame.x, frame.y, frame.w, frame.h),
+                            },
+                        )
+                    })
+                    .collect(),
+                sequence: meta.sequence,
+            }
+        } else if path.is_file() {
+            self.set_atlas_image(context, vec![path.to_path_buf()])?
+        } else if folder.is_dir() {
+            let mut list: Vec<std::path::PathBuf> = std::fs::read_dir(folder)?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|extension| extension == "png"))
+                .collect();
+
+            list.sort();
+
+            self.set_atlas_image(context, list)?
+        } else {
+            return Err(anyhow::Error::msg(format!(
+                "Asset::set_atlas(): Could not find atlas file or image folder for \"{name}\"."
+            )));
+        };
+
+        self.atlas.insert(name.to_string(), atlas);
+
+        self.get_atlas(name)
+    }
+
+    /// Create an atlas out of loose image files, one frame per file.
+    fn set_atlas_image(
+        &mut self,
+        context: &mut Context,
+        list: Vec<std::path::PathBuf>,
+    ) -> anyhow::Result<AssetAtlas> {
+        let mut frame = HashMap::default();
+
+        for path in list {
+            let texture = path.to_string_lossy().to_string();
+            let data = self.set_texture(context, &texture)?;
+            let shape = Rectangle::new(0.0, 0.0, data.width as f32, data.height as f32);
+            let name = path
+                .file_stem()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            frame.insert(name, AtlasShape { texture, shape });
+        }
+
+        Ok(AssetAtlas {
+            frame,
+            sequence: HashMap::default(),
+        })
+    }
+
+    /// Get an atlas.
+    pub fn get_atlas(&self, name: &str) -> anyhow::Result<&AssetAtlas> {
+        self.atlas.get(name).ok_or(anyhow::Error::msg(format!(
+            "Asset::get_atlas(): Could not find asset \"{name}\"."
+        )))
+    }
+
+    /// Check if the resource map has an atlas.
+    pub fn has_atlas(&self, name: &str) -> bool {
+        self.atlas.contains_key(name)
+    }
+
+    /// Get a sprite (a frame) from an atlas.
+    pub fn get_sprite(&self, atlas: &str, frame: &str) -> anyhow::Result<Sprite<'_>> {
+        let data = self.get_atlas(atlas)?;
+        let shape = data.frame.get(frame).ok_or(anyhow::Error::msg(format!(
+            "Asset::get_sprite(): Could not find frame \"{frame}\" in atlas \"{atlas}\"."
+        )))?;
+
+        Ok(Sprite {
+            texture: self.get_atlas_texture(shape)?,
+            shape: shape.shape,
+        })
+    }
+
+    /// Get a sprite from an atlas sequence, at a given time (in seconds) since the start of the sequence.
+    /// A sequence missing from the atlas falls back to a still frame of the same name, such as
+    /// the frame of an atlas made out of loose image files.
+    pub fn get_sprite_sequence(
+        &self,
+        atlas: &str,
+        sequence: &str,
+        time: f32,
+    ) -> anyhow::Result<Sprite<'_>> {
+        let data = self.get_atlas(atlas)?;
+
+        match data.sequence.get(sequence) {
+            Some(entry) => self.get_sprite(atlas, entry.frame_at(time)?),
+            None if data.frame.contains_key(sequence) => self.get_sprite(atlas, sequence),
+            None => Err(anyhow::Error::msg(format!(
+                "Asset::get_sprite_sequence(): Could not find sequence \"{sequence}\" in atlas \"{atlas}\"."
+            ))),
+        }
+    }
+
+    /// Check that a sequence, or a still frame of the same name, can be drawn from an atlas: every
+    /// frame it uses is in the atlas, and has its texture loaded.
+    pub fn validate_sequence(&self, atlas: &str, sequence: &str) -> anyhow::Result<()> {
+        let data = self.get_atlas(atlas)?;
+
+        match data.sequence.get(sequence) {
+            Some(entry) => {
+                if entry.frame.is_empty() {
+                    return Err(anyhow::Error::msg(format!(
+                        "Asset::validate_sequence(): Sequence \"{sequence}\" in atlas \"{atlas}\" has no frame."
+                    )));
+                }
+
+                for frame in &entry.frame {
+                    self.get_sprite(atlas, frame)?;
+                }
+            }
+            None => {
+                self.get_sprite(atlas, sequence)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_atlas_texture(&self, shape: &AtlasShape) -> anyhow::Result<&Texture2D> {
+        self.texture
+            .get(&shape.texture)
+            .ok_or(anyhow::Error::msg(format!(
+                "Asset::get_atlas_texture(): Could not find asset \"{}\".",
+                shape.texture
+            )))
+    }
 }
 
 impl Drop for Asset<'_> {
@@ -312,10 +591,265 @@ impl Drop for Asset<'_> {
 pub struct AssetModel {
     /// Handle to the R3D model data.
     pub model: Model,
+    /// Model-space bounding box, computed once on load.
+    pub bound: BoundingBox,
+    /// Level of detail list, by increasing switch distance.
+    pub level: Vec<ModelLevel>,
+    /// Distance fade-out, if any.
+    pub fade: Option<ModelFade>,
     /// Handle to the R3D model animation data.
     pub animation: Vec<ModelAnimation>,
+    /// Model-specific material data, one entry per material slot.
+    pub material: Vec<ModelMaterial>,
     /// Model-specific animation event data.
     pub event: ModelEvent,
+    /// Model-specific animation state graph, if any.
+    pub graph: Option<AnimationGraph>,
+}
+
+impl AssetModel {
+    /// Get an animation clip by name.
+    pub fn get_animation(&self, name: &str) -> anyhow::Result<&ModelAnimation> {
+        Self::find_animation(&self.animation, name)
+    }
+
+    /// Find an animation clip by name, in a clip list. Clips with no frame are rejected.
+    pub fn find_animation<'a>(
+        animation: &'a [ModelAnimation],
+        name: &str,
+    ) -> anyhow::Result<&'a ModelAnimation> {
+        let animation = animation
+            .iter()
+            .find(|animation| name_from_raw(&animation.name) == name)
+            .ok_or(anyhow::Error::msg(format!(
+                "AssetModel::find_animation(): Could not find animation \"{name}\"."
+            )))?;
+
+        if animation.frameCount <= 0
+            || animation.boneCount <= 0
+            || animation.framePoses.is_null()
+            || animation.bones.is_null()
+        {
+            return Err(anyhow::Error::msg(format!(
+                "AssetModel::find_animation(): Animation \"{name}\" is empty."
+            )));
+        }
+
+        Ok(animation)
+    }
+
+    /// Get the index of a bone by name, in the model's skeleton.
+    pub fn get_bone(&self, name: &str) -> Option<usize> {
+        if self.model.bones.is_null() {
+            return None;
+        }
+
+        let bone =
+            unsafe { std::slice::from_raw_parts(self.model.bones, self.model.boneCount as usize) };
+
+        bone.iter()
+            .position(|bone| name_from_raw(&bone.name) == name)
+    }
+
+    /// Get the material data for a given mesh.
+    pub fn mesh_material(&self, mesh: usize) -> &ModelMaterial {
+        let index = unsafe { *self.model.meshMaterial.wrapping_add(mesh) } as usize;
+
+        &self.material[index]
+    }
+
+    /// Get the model path to draw at a given distance from the camera. None if the base model
+    /// should be drawn.
+    pub fn get_level(&self, distance: f32) -> Option<&str> {
+        self.level
+            .iter()
+            .rev()
+            .find(|level| distance >= level.distance)
+            .map(|level| level.path.as_str())
+    }
+
+    /// Get the alpha multiplier for a given distance from the camera, from the distance fade.
+    pub fn get_fade(&self, distance: f32) -> f32 {
+        match &self.fade {
+            Some(fade) => 1.0 - percentage_from_value(distance, fade.min, fade.max).clamp(0.0, 1.0),
+            None => 1.0,
+        }
+    }
+
+    /// Check if any mesh in the model is alpha blended.
+    pub fn has_blend(&self) -> bool {
+        (0..self.model.meshes().len())
+            .any(|mesh| self.mesh_material(mesh).alpha == AlphaMode::Blend)
+    }
+
+    /// Get the surface kind of every mesh in the model.
+    pub fn mesh_surface(&self) -> Vec<SurfaceKind> {
+        (0..self.model.meshes().len())
+            .map(|mesh| self.mesh_material(mesh).surface)
+            .collect()
+    }
+}
+
+/// A level of detail for a model, from its meta file.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModelLevel {
+    /// Path to the level's model. Materials and animations are the level model's own.
+    pub path: String,
+    /// Camera distance from which this level is drawn instead of the previous one.
+    pub distance: f32,
+}
+
+impl ModelLevel {
+    /// Validate a level list and distance fade: switch distances must be positive and increasing,
+    /// and the fade must end after it begins.
+    pub fn validate(list: &[Self], fade: Option<&ModelFade>) -> anyhow::Result<()> {
+        let mut last = 0.0;
+
+        for level in list {
+            if level.distance <= last {
+                return Err(anyhow::Error::msg(format!(
+                    "ModelLevel::validate(): Level \"{}\" has a distance of {}, which must be greater than {last}.",
+                    level.path, level.distance
+                )));
+            }
+
+            last = level.distance;
+        }
+
+        if let Some(fade) = fade
+            && (fade.min < 0.0 || fade.max <= fade.min)
+        {
+            return Err(anyhow::Error::msg(format!(
+                "ModelLevel::validate(): Fade range ({}, {}) is invalid.",
+                fade.min, fade.max
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Distance fade-out for a model, from its meta file. Instances begin fading at "min" and are
+/// culled past "max".
+#[derive(Debug, Deserialize, Copy, Clone)]
+pub struct ModelFade {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Model material data.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ModelMaterial {
+    /// Material slot index in the model. Required, as the entry name is only for reference.
+    pub index: usize,
+    /// Albedo texture path.
+    #[serde(default)]
+    pub albedo: Option<String>,
+    /// Normal texture path.
+    #[serde(default)]
+    pub normal: Option<String>,
+    /// Emissive texture path.
+    #[serde(default)]
+    pub emissive: Option<String>,
+    /// Roughness texture path.
+    #[serde(default)]
+    pub roughness: Option<String>,
+    /// Albedo tint.
+    #[serde(default = "ModelMaterial::tint_default")]
+    pub tint: Color,
+    /// Alpha mode.
+    #[serde(default)]
+    pub alpha: AlphaMode,
+    /// Draw both faces of each triangle.
+    #[serde(default)]
+    pub double: bool,
+    /// Surface kind, for foot-step and impact effects.
+    #[serde(default)]
+    pub surface: SurfaceKind,
+}
+
+impl Default for ModelMaterial {
+    fn default() -> Self {
+        Self {
+            index: usize::default(),
+            albedo: None,
+            normal: None,
+            emissive: None,
+            roughness: None,
+            tint: Self::tint_default(),
+            alpha: AlphaMode::default(),
+            double: false,
+            surface: SurfaceKind::default(),
+        }
+    }
+}
+
+impl ModelMaterial {
+    fn tint_default() -> Color {
+        Color::WHITE
+    }
+
+    /// Get the texture map bit-flag for the light shader. Albedo is always present.
+    pub fn map_flag(&self) -> i32 {
+        let mut flag = 0;
+
+        if self.normal.is_some() {
+            flag |= 1 << 0;
+        }
+
+        if self.emissive.is_some() {
+            flag |= 1 << 1;
+        }
+
+        if self.roughness.is_some() {
+            flag |= 1 << 2;
+        }
+
+        flag
+    }
+}
+
+/// Every possible alpha mode for a material.
+#[derive(Debug, Deserialize, Default, Copy, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AlphaMode {
+    /// Alpha is ignored.
+    #[default]
+    Opaque,
+    /// Alpha under the cut-off value is discarded.
+    Mask { cutoff: f32 },
+    /// Alpha is blended, and drawn after every opaque mesh.
+    Blend,
+}
+
+impl AlphaMode {
+    /// Get the alpha cut-off value for the light shader.
+    pub fn cutoff(&self) -> f32 {
+        match self {
+            Self::Mask { cutoff } => *cutoff,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Every possible surface kind for a material.
+#[derive(Debug, Deserialize, Default, Copy, Clone, PartialEq)]
+pub enum SurfaceKind {
+    #[default]
+    Concrete = 0,
+    Metal = 1,
+    Wood = 2,
+}
+
+impl SurfaceKind {
+    /// Get a surface kind from a geometry collider's user-data.
+    pub fn from_data(data: u128) -> Self {
+        match data {
+            1 => Self::Metal,
+            2 => Self::Wood,
+            _ => Self::Concrete,
+        }
+    }
 }
 
 /// Model animation event data.
@@ -326,7 +860,15 @@ pub struct ModelEvent {
 
 impl ModelEvent {
     fn from_model_meta(meta: ModelMeta) -> Self {
-        if let Some(animation) = meta.event {
+        if let Some(mut animation) = meta.event {
+            for frame in animation.values_mut() {
+                for event in frame.values_mut() {
+                    if let AnimationEvent::Sound { path } = event {
+                        *path = ModelMeta::get_data_path(path);
+                    }
+                }
+            }
+
             Self { map: animation }
         } else {
             Self::default()
@@ -334,23 +876,39 @@ impl ModelEvent {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum AnimationEvent {
     Sound { path: String },
+    Particle { name: String },
     Custom(HashMap<String, serde_json::Value>),
 }
 
 #[derive(Debug, Deserialize, Default)]
-struct ModelMeta {
-    texture: Option<Vec<String>>,
-    event: Option<HashMap<String, HashMap<i32, AnimationEvent>>>,
+pub struct ModelMeta {
+    pub texture: Option<Vec<String>>,
+    pub material: Option<HashMap<String, ModelMaterial>>,
+    pub event: Option<HashMap<String, HashMap<i32, AnimationEvent>>>,
+    pub graph: Option<AnimationGraph>,
+    pub level: Option<Vec<ModelLevel>>,
+    pub fade: Option<ModelFade>,
 }
 
 impl ModelMeta {
-    const FILE_EXTENSION: &str = "meta";
+    pub const FILE_EXTENSION: &str = "meta";
+    const PATH_DATA: &str = "data";
+
+    /// Get the path to a file named in a meta file, such as a texture or a sound. Meta file paths
+    /// are relative to the data folder; a path already inside the data folder is kept as is.
+    pub fn get_data_path(path: &str) -> String {
+        if path.starts_with(&format!("{}/", Self::PATH_DATA)) {
+            path.to_string()
+        } else {
+            format!("{}/{path}", Self::PATH_DATA)
+        }
+    }
 
-    fn new(path: &str) -> anyhow::Result<Self> {
+    pub fn new(path: &str) -> anyhow::Result<Self> {
         let path: Vec<&str> = path.split(".").collect();
         let path = format!("{}.{}", path[0], Self::FILE_EXTENSION);
 
@@ -364,6 +922,141 @@ impl ModelMeta {
 
 //================================================================
 
+/// A texture atlas.
+pub struct AssetAtlas {
+    /// Frame list.
+    pub frame: HashMap<String, AtlasShape>,
+    /// Animated frame sequence list.
+    pub sequence: HashMap<String, AtlasSequence>,
+}
+
+/// A frame in an atlas.
+pub struct AtlasShape {
+    /// Path to the frame's texture, in the texture hash-map.
+    pub texture: String,
+    /// Frame rectangle in the texture.
+    pub shape: Rectangle,
+}
+
+/// An animated frame sequence in an atlas.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AtlasSequence {
+    /// Frame name list, in play-back order.
+    pub frame: Vec<String>,
+    /// Frame rate, in frames per second.
+    pub rate: f32,
+    /// Loop back to the first frame at the end of the sequence.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+impl AtlasSequence {
+    /// Get the frame name at a given time (in seconds) since the start of the sequence.
+    pub fn frame_at(&self, time: f32) -> anyhow::Result<&str> {
+        if self.frame.is_empty() {
+            return Err(anyhow::Error::msg(
+                "AtlasSequence::frame_at(): Sequence has no frame.",
+            ));
+        }
+
+        let index = (time.max(0.0) * self.rate) as usize;
+        let index = if self.repeat {
+            index % self.frame.len()
+        } else {
+            index.min(self.frame.len() - 1)
+        };
+
+        Ok(&self.frame[index])
+    }
+
+    /// Get the length of the sequence, in seconds.
+    pub fn length(&self) -> f32 {
+        self.frame.len() as f32 / self.rate
+    }
+}
+
+#[derive(Deserialize)]
+struct AtlasMeta {
+    texture: String,
+    frame: HashMap<String, AtlasFrame>,
+    #[serde(default)]
+    sequence: HashMap<String, AtlasSequence>,
+}
+
+#[derive(Deserialize)]
+struct AtlasFrame {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+/// A frame from an atlas.
+#[derive(Copy, Clone)]
+pub struct Sprite<'a> {
+    /// Atlas texture.
+    pub texture: &'a Texture2D,
+    /// Frame rectangle in the atlas texture.
+    pub shape: Rectangle,
+}
+
+//================================================================
+
+/// A font, with a look-up table for every code point it has a glyph for.
+pub struct AssetFont {
+    /// Handle to the font data.
+    pub font: Font,
+    /// Glyph index, by code point. Only code points with an actual glyph in the font file.
+    glyph: HashMap<char, usize>,
+    /// Every code point asked for at load time.
+    request: String,
+}
+
+impl AssetFont {
+    fn new(font: Font, request: String) -> Self {
+        let mut glyph = HashMap::default();
+
+        for (i, info) in font.chars().iter().enumerate() {
+            // a code point missing from the font file is still baked, but with an empty image.
+            if let Some(code) = char::from_u32(info.value as u32)
+                && (info.image.width > 0 || code.is_whitespace())
+            {
+                glyph.insert(code, i);
+            }
+        }
+
+        Self {
+            font,
+            glyph,
+            request,
+        }
+    }
+
+    /// Check if the font has a glyph for a code point.
+    pub fn has_glyph(&self, code: char) -> bool {
+        self.glyph.contains_key(&code)
+    }
+
+    /// Get the horizontal advance for a code point, at a given font size. A code point with no
+    /// glyph uses the advance of the fall-back glyph.
+    pub fn get_advance(&self, code: char, size: f32) -> f32 {
+        let list = self.font.chars();
+        let info = match self.glyph.get(&code) {
+            Some(index) => list.get(*index),
+            None => list.iter().find(|info| info.value == '?' as i32),
+        };
+        let scale = size / self.font.base_size() as f32;
+
+        match info {
+            Some(info) if info.advanceX != 0 => info.advanceX as f32 * scale,
+            Some(info) => (info.image.width + info.offsetX) as f32 * scale,
+            None => 0.0,
+        }
+    }
+}
+
+//================================================================
+
 /// A sound.
 pub struct AssetSound<'a> {
     /// Handle to the sound data.
@@ -0,0 +1,577 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::debug::*;
+use crate::helper::*;
+use crate::user::*;
+
+//================================================================
+
+use rand::Rng;
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// A camera pose, before shake and field of view kick.
+#[derive(Debug, Copy, Clone)]
+pub struct CameraPose {
+    pub point: Vector3,
+    pub focus: Vector3,
+    pub up: Vector3,
+    /// Field of view, in degrees.
+    pub field: f32,
+}
+
+impl CameraPose {
+    pub fn new(point: Vector3, focus: Vector3, up: Vector3, field: f32) -> Self {
+        Self {
+            point,
+            focus,
+            up,
+            field,
+        }
+    }
+
+    /// Blend this pose to another pose.
+    fn blend(&self, other: &Self, time: f32) -> Self {
+        Self {
+            point: self.point.lerp(other.point, time),
+            focus: self.focus.lerp(other.focus, time),
+            up: self.up.lerp(other.up, time).normalized(),
+            field: interpolate(self.field, other.field, time),
+        }
+    }
+}
+
+impl Default for CameraPose {
+    fn default() -> Self {
+        Self {
+            point: Vector3::zero(),
+            focus: Vector3::new(0.0, 0.0, 1.0),
+            up: Vector3::up(),
+            field: 90.0,
+        }
+    }
+}
+
+//================================================================
+
+/// A node in a camera path.
+#[derive(Debug, Deserialize, Copy, Clone)]
+pub struct CameraNode {
+    pub point: Vector3,
+    pub focus: Vector3,
+    /// Field of view, in degrees.
+    #[serde(default = "CameraNode::field_default")]
+    pub field: f32,
+}
+
+impl CameraNode {
+    fn field_default() -> f32 {
+        90.0
+    }
+}
+
+/// A spline camera path, for cut-scenes, from a "data/camera/*.json" file. Point and focus follow
+/// a Catmull-Rom spline through every node, with every node evenly spaced in time.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CameraPath {
+    pub node: Vec<CameraNode>,
+    /// Length of the whole path, in seconds.
+    pub length: f32,
+    /// Start over from the first node once the path is over.
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+impl CameraPath {
+    /// Path to the camera path folder.
+    const PATH: &str = "data/camera";
+
+    /// Load a camera path by name.
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        Self::load(&Self::get_path(name))
+    }
+
+    /// Get the path to a camera path by name.
+    pub fn get_path(name: &str) -> String {
+        format!("{}/{name}.json", Self::PATH)
+    }
+
+    /// Check if the path is over at a given time. A repeating path is never over.
+    pub fn is_finish(&self, time: f32) -> bool {
+        !self.repeat && time >= self.length
+    }
+
+    /// Get the pose at a given time, in seconds from the start of the path.
+    pub fn get_pose(&self, time: f32) -> CameraPose {
+        let count = self.node.len();
+
+        if count == 0 {
+            return CameraPose::default();
+        }
+
+        let time = if self.repeat {
+            time.rem_euclid(self.length) / self.length
+        } else {
+            (time / self.length).clamp(0.0, 1.0)
+        };
+        let segment = time * (count - 1) as f32;
+        let index = (segment as usize).min(count.saturating_sub(2));
+        let local = (segment - index as f32).clamp(0.0, 1.0);
+
+        let node = |i: usize| &self.node[i.min(count - 1)];
+        let a = node(index.saturating_sub(1));
+        let b = node(index);
+        let c = node(index + 1);
+        let d = node(index + 2);
+
+        CameraPose::new(
+            catmull_rom(a.point, b.point, c.point, d.point, local),
+            catmull_rom(a.focus, b.focus, c.focus, d.focus, local),
+            Vector3::up(),
+            interpolate(b.field, c.field, local),
+        )
+    }
+}
+
+impl Definition for CameraPath {
+    fn validate(&self) -> Result<(), String> {
+        if self.node.len() < 2 {
+            return Err("Path must have at least two nodes.".to_string());
+        }
+
+        if self.length <= 0.0 {
+            return Err("Path length must be positive.".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Get a point on a Catmull-Rom spline segment, from "b" to "c".
+fn catmull_rom(a: Vector3, b: Vector3, c: Vector3, d: Vector3, time: f32) -> Vector3 {
+    let t2 = time * time;
+    let t3 = t2 * time;
+
+    (b * 2.0
+        + (c - a) * time
+        + (a * 2.0 - b * 5.0 + c * 4.0 - d) * t2
+        + (b * 3.0 - a - c * 3.0 + d) * t3)
+        * 0.5
+}
+
+//================================================================
+
+/// A camera source. Only the source with the highest priority is active at a time.
+pub enum CameraSource {
+    /// Pose given by an entity on every frame, such as the player's first-person view.
+    Entity { pose: CameraPose },
+    /// Free-flying camera, driven by the mouse and the movement keys.
+    Spectator { point: Vector3, angle: Vector2 },
+    /// Fixed pose, such as a security camera.
+    Fixed { pose: CameraPose },
+    /// Spline path, for cut-scenes.
+    Path { path: CameraPath, time: f32 },
+}
+
+impl CameraSource {
+    /// Create a new spectator source, from a pose.
+    pub fn new_spectator(pose: &CameraPose) -> Self {
+        let forward = (pose.focus - pose.point).normalized();
+
+        Self::Spectator {
+            point: pose.point,
+            angle: Vector2::new(
+                forward.x.atan2(forward.z).to_degrees(),
+                (-forward.y).clamp(-1.0, 1.0).asin().to_degrees(),
+            ),
+        }
+    }
+}
+
+/// Handle to an attached camera source. A handle is only valid for the source it was given for:
+/// once that source is detached, the handle no longer refers to any source, even if its slot is
+/// taken by another one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CameraHandle {
+    index: usize,
+    order: usize,
+}
+
+impl Default for CameraHandle {
+    fn default() -> Self {
+        Self {
+            index: usize::MAX,
+            order: usize::MAX,
+        }
+    }
+}
+
+struct CameraEntry {
+    source: CameraSource,
+    priority: i32,
+    /// Blend length when this source becomes active, in seconds.
+    blend: f32,
+    /// Attach order, so that the latest source wins among sources of equal priority.
+    order: usize,
+}
+
+/// The scene's camera: a prioritized stack of camera sources, with blend transitions between
+/// sources, trauma-based shake and field of view kicks.
+#[derive(Default)]
+pub struct CameraSystem {
+    /// Source slot list. Removed sources leave an empty slot so indices stay valid, and a handle
+    /// is checked against the attach order of its slot's source.
+    source_list: Vec<Option<CameraEntry>>,
+    /// Attach count, for the attach order of each source.
+    order: usize,
+    /// Index of the active source.
+    active: Option<usize>,
+    /// Pose at the start of the current blend, and the time since the start of the blend.
+    blend: Option<(CameraPose, f32)>,
+    /// Last pose, before shake and kick.
+    pose: CameraPose,
+    /// Shake trauma, from 0.0 to 1.0. Shake is the square of trauma.
+    trauma: f32,
+    /// Field of view kick, in degrees at a field of view of "FIELD_BASE".
+    kick: f32,
+    /// Handle to the debug free camera source, if enabled.
+    free: Option<CameraHandle>,
+}
+
+impl CameraSystem {
+    /// Priority for the player's first-person view.
+    pub const PRIORITY_PLAYER: i32 = 0;
+    /// Priority for a fixed camera, such as a security camera.
+    pub const PRIORITY_FIXED: i32 = 10;
+    /// Priority for a cut-scene camera path.
+    pub const PRIORITY_PATH: i32 = 20;
+    /// Priority for the debug free camera.
+    pub const PRIORITY_FREE: i32 = 100;
+    /// Default blend length, in seconds.
+    pub const BLEND: f32 = 0.5;
+    /// Trauma decay, per second.
+    const TRAUMA_DECAY: f32 = 1.5;
+    /// Shake point offset at full trauma.
+    const SHAKE_POINT: f32 = 0.15;
+    /// Shake roll at full trauma, in degrees.
+    const SHAKE_ANGLE: f32 = 4.0;
+    /// Field of view kick decay rate.
+    const KICK_DECAY: f32 = 2.0;
+    /// Field of view for which a kick is given, so that kicks scale with the user's field of view.
+    const FIELD_BASE: f32 = 90.0;
+    /// Spectator speed, in units per second.
+    const SPECTATOR_SPEED: f32 = 8.0;
+    const SPECTATOR_ANGLE_MIN: f32 = -89.0;
+    const SPECTATOR_ANGLE_MAX: f32 = 89.0;
+    /// Line count per path segment, for the debug draw.
+    const DEBUG_PATH_STEP: usize = 8;
+
+    /// Attach a new camera source. Returns the handle to the source.
+    pub fn attach(&mut self, source: CameraSource, priority: i32, blend: f32) -> CameraHandle {
+        let order = self.order;
+        let entry = CameraEntry {
+            source,
+            priority,
+            blend,
+            order,
+        };
+
+        self.order += 1;
+
+        let index = if let Some(index) = self.source_list.iter().position(|slot| slot.is_none()) {
+            self.source_list[index] = Some(entry);
+            index
+        } else {
+            self.source_list.push(Some(entry));
+            self.source_list.len() - 1
+        };
+
+        CameraHandle { index, order }
+    }
+
+    /// Detach a camera source. Does nothing if the source has already been detached.
+    pub fn detach(&mut self, handle: CameraHandle) {
+        if self.get_entry(handle).is_some() {
+            self.source_list[handle.index] = None;
+        }
+    }
+
+    /// Set the pose of an entity or fixed source.
+    pub fn set_pose(&mut self, handle: CameraHandle, pose: CameraPose) {
+        if let Some(entry) = self.get_entry_mutable(handle)
+            && let CameraSource::Entity { pose: value } | CameraSource::Fixed { pose: value } =
+                &mut entry.source
+        {
+            *value = pose;
+        }
+    }
+
+    /// Check if a source is the active one.
+    pub fn is_active(&self, handle: CameraHandle) -> bool {
+        self.active == Some(handle.index) && self.get_entry(handle).is_some()
+    }
+
+    /// Get the entry of a source, if the handle is still valid.
+    fn get_entry(&self, handle: CameraHandle) -> Option<&CameraEntry> {
+        self.source_list
+            .get(handle.index)?
+            .as_ref()
+            .filter(|entry| entry.order == handle.order)
+    }
+
+    /// Get the entry of a source, if the handle is still valid.
+    fn get_entry_mutable(&mut self, handle: CameraHandle) -> Option<&mut CameraEntry> {
+        self.source_list
+            .get_mut(handle.index)?
+            .as_mut()
+            .filter(|entry| entry.order == handle.order)
+    }
+
+    /// Add shake trauma, from 0.0 to 1.0.
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// Add a field of view kick, in degrees at a field of view of 90.0. The kick is scaled to the
+    /// user's field of view, and decays over time.
+    pub fn add_kick(&mut self, kick: f32) {
+        self.kick = self.kick.max(kick);
+    }
+
+    /// Toggle the debug free camera, from the current pose.
+    pub fn toggle_free(&mut self) {
+        if let Some(free) = self.free.take() {
+            self.detach(free);
+        } else {
+            self.free = Some(self.attach(
+                CameraSource::new_spectator(&self.pose),
+                Self::PRIORITY_FREE,
+                0.0,
+            ));
+        }
+    }
+
+    /// Push every path source to the debug draw, for the current tick: the spline, each node, and
+    /// the current pose.
+    pub fn draw_debug(&self, debug: &mut DebugDraw) {
+        for entry in self.source_list.iter().flatten() {
+            if let CameraSource::Path { path, time } = &entry.source {
+                let count = (path.node.len() - 1) * Self::DEBUG_PATH_STEP;
+                let mut last = path.get_pose(0.0).point;
+
+                for i in 1..=count {
+                    // a repeating path wraps back to the start on its last frame.
+                    let point = if i == count {
+                        path.node[path.node.len() - 1].point
+                    } else {
+                        path.get_pose(path.length * i as f32 / count as f32).point
+                    };
+
+                    debug.line(DebugCategory::Path, last, point, Color::YELLOW, 0.0);
+                    last = point;
+                }
+
+                for node in &path.node {
+                    debug.sphere(DebugCategory::Path, node.point, 0.1, Color::YELLOW, 0.0);
+                }
+
+                let pose = path.get_pose(*time);
+
+                debug.arrow(
+                    DebugCategory::Path,
+                    pose.point,
+                    pose.focus,
+                    Color::ORANGE,
+                    0.0,
+                );
+            }
+        }
+    }
+
+    /// Update the active source, blend, shake and kick. Returns the camera to draw with, or None if
+    /// there is no source. A path source is detached once its path is over.
+    pub fn update(&mut self, user: &User, handle: &RaylibHandle, time: f32) -> Option<Camera3D> {
+        // the active source is the one with the highest priority, and the latest one among equals.
+        let active = self
+            .source_list
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| entry.as_ref().map(|entry| (i, entry)))
+            .max_by_key(|(_, entry)| (entry.priority, entry.order))
+            .map(|(i, _)| i);
+
+        let entry = self.source_list[active?].as_mut()?;
+
+        if active != self.active {
+            // the first source, or a source with no blend, is cut to.
+            self.blend = if self.active.is_some() && entry.blend > 0.0 {
+                Some((self.pose, 0.0))
+            } else {
+                None
+            };
+            self.active = active;
+        }
+
+        let target = match &mut entry.source {
+            CameraSource::Entity { pose } | CameraSource::Fixed { pose } => *pose,
+            CameraSource::Spectator { point, angle } => {
+                Self::spectator(user, handle, time, point, angle)
+            }
+            CameraSource::Path {
+                path,
+                time: path_time,
+            } => {
+                *path_time += time;
+                path.get_pose(*path_time)
+            }
+        };
+        let finish =
+            matches!(&entry.source, CameraSource::Path { path, time } if path.is_finish(*time));
+
+        self.pose = match &mut self.blend {
+            Some((from, blend_time)) => {
+                *blend_time += time;
+
+                let scale = (*blend_time / entry.blend).min(1.0);
+                let pose = from.blend(&target, ease_in_out_cubic(scale));
+
+                if scale >= 1.0 {
+                    self.blend = None;
+                }
+
+                pose
+            }
+            None => target,
+        };
+
+        if finish && let Some(active) = active {
+            self.source_list[active] = None;
+        }
+
+        //================================================================
+
+        self.trauma = (self.trauma - time * Self::TRAUMA_DECAY).max(0.0);
+        self.kick -= self.kick * (time * Self::KICK_DECAY).min(1.0);
+
+        let shake = self.trauma * self.trauma * user.video_shake;
+        let forward = (self.pose.focus - self.pose.point).normalized();
+        let (offset, roll) = if shake > 0.0 {
+            let mut random = rand::rng();
+
+            (
+                Vector3::new(
+                    random.random_range(-1.0..1.0),
+                    random.random_range(-1.0..1.0),
+                    random.random_range(-1.0..1.0),
+                ) * shake
+                    * Self::SHAKE_POINT,
+                random.random_range(-1.0..1.0) * shake * Self::SHAKE_ANGLE,
+            )
+        } else {
+            (Vector3::zero(), 0.0)
+        };
+
+        let field = self.pose.field + self.kick * user.video_field / Self::FIELD_BASE;
+
+        Some(Camera3D::perspective(
+            self.pose.point + offset,
+            self.pose.focus + offset,
+            vector_3_rotate_by_axis_angle(self.pose.up, forward, roll.to_radians()),
+            field.clamp(1.0, 179.0),
+        ))
+    }
+
+    /// Move a spectator source, and get its pose.
+    fn spectator(
+        user: &User,
+        handle: &RaylibHandle,
+        time: f32,
+        point: &mut Vector3,
+        angle: &mut Vector2,
+    ) -> CameraPose {
+        let mouse = handle.get_mouse_delta();
+
+        angle.x -= mouse.x * 0.1 * user.input_mouse_scale;
+        angle.y += mouse.y * 0.1 * user.input_mouse_scale;
+        angle.x %= 359.0;
+        angle.y = angle
+            .y
+            .clamp(Self::SPECTATOR_ANGLE_MIN, Self::SPECTATOR_ANGLE_MAX);
+
+        let direction = Direction::new_from_angle(&Vector3::new(angle.x, angle.y, 0.0));
+        let key = |a: Input, b: Input| {
+            if a.get_down(handle) {
+                1.0
+            } else if b.get_down(handle) {
+                -1.0
+            } else {
+                0.0
+            }
+        };
+        let speed = Self::SPECTATOR_SPEED * time;
+
+        *point += direction.x * key(user.input_move_x_a, user.input_move_x_b) * speed;
+        *point += direction.z * key(user.input_move_z_a, user.input_move_z_b) * speed;
+
+        if user.input_jump.get_down(handle) {
+            *point += Vector3::up() * speed;
+        }
+
+        CameraPose::new(
+            *point,
+            *point + direction.x,
+            Vector3::up(),
+            user.video_field,
+        )
+    }
+}
@@ -0,0 +1,398 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+use crate::helper::*;
+use crate::user::*;
+
+//================================================================
+
+use hashbrown::HashMap;
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// A caption for a sound, or a timeline subtitle.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Caption {
+    pub text: String,
+    /// Speaker name, shown before the text.
+    #[serde(default)]
+    pub speaker: Option<String>,
+    #[serde(default = "Caption::color_default")]
+    pub color: Color,
+    /// A caption with a higher priority is shown before any caption with a lower priority.
+    #[serde(default)]
+    pub priority: i32,
+    /// Length on screen, in seconds. Zero to pick a length from the text length.
+    #[serde(default)]
+    pub length: f32,
+    /// Non-speech sound effect caption, such as "[door opens]".
+    #[serde(default)]
+    pub effect: bool,
+}
+
+impl Caption {
+    /// Minimum length on screen, in seconds.
+    const LENGTH_MIN: f32 = 1.5;
+    /// Length on screen for each character, in seconds.
+    const LENGTH_CHARACTER: f32 = 0.06;
+
+    fn color_default() -> Color {
+        Color::WHITE
+    }
+
+    /// Check the caption for any value that can not be shown.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.text.is_empty() {
+            return Err("Text is empty.".to_string());
+        }
+
+        if self.length < 0.0 {
+            return Err("Length must not be negative.".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Get the length on screen, in seconds.
+    pub fn get_length(&self) -> f32 {
+        if self.length > 0.0 {
+            self.length
+        } else {
+            (self.text.chars().count() as f32 * Self::LENGTH_CHARACTER).max(Self::LENGTH_MIN)
+        }
+    }
+
+    /// Get the text to draw, with the speaker name if any.
+    pub fn get_text(&self) -> String {
+        match &self.speaker {
+            Some(speaker) => format!("{speaker}: {}", self.text),
+            None => self.text.clone(),
+        }
+    }
+
+    /// Check if the user wants this caption to be shown.
+    fn is_visible(&self, user: &User) -> bool {
+        if self.effect {
+            user.video_caption
+        } else {
+            user.video_subtitle
+        }
+    }
+}
+
+//================================================================
+
+/// Caption table, from a "data/caption/{idiom}.json" file: every caption, by sound path.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(transparent)]
+pub struct CaptionTable {
+    pub map: HashMap<String, Caption>,
+    /// Every caption dropped on load for a value that can not be shown, with the reason.
+    #[serde(skip)]
+    pub failure: Vec<String>,
+}
+
+impl CaptionTable {
+    /// Path to the caption table folder.
+    const PATH: &str = "data/caption";
+
+    /// Load the caption table for an idiom. Any sound missing from the table falls back to the
+    /// English table. A missing table file is treated as an empty table. A caption that can not be
+    /// shown is dropped, and kept in the failure list.
+    pub fn new(idiom: IdiomKind) -> anyhow::Result<Self> {
+        let mut table = Self::new_file(IdiomKind::English)?;
+
+        if idiom != IdiomKind::English {
+            let other = Self::new_file(idiom)?;
+
+            table.map.extend(other.map);
+            table.failure.extend(other.failure);
+        }
+
+        Ok(table)
+    }
+
+    /// Get the path to the caption table for an idiom.
+    pub fn get_path(idiom: IdiomKind) -> String {
+        format!("{}/{}.json", Self::PATH, idiom.folder_name())
+    }
+
+    /// Get every code point in use by the table: every caption text and speaker name.
+    pub fn get_code_point(&self) -> String {
+        let mut code = String::new();
+
+        for caption in self.map.values() {
+            code.push_str(&caption.get_text());
+        }
+
+        code
+    }
+
+    fn new_file(idiom: IdiomKind) -> anyhow::Result<Self> {
+        let path = Self::get_path(idiom);
+
+        if !std::path::Path::new(&path).is_file() {
+            return Ok(Self::default());
+        }
+
+        let file = std::fs::read_to_string(&path)?;
+        let mut data: Self = serde_json::from_str(&file).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "CaptionTable::new(): Could not parse file \"{path}\": {error}"
+            ))
+        })?;
+
+        let mut failure = Vec::new();
+
+        data.map.retain(|sound, caption| match caption.validate() {
+            Ok(()) => true,
+            Err(error) => {
+                failure.push(format!(
+                    "CaptionTable::new(): \"{path}\": \"{sound}\": {error}"
+                ));
+                false
+            }
+        });
+
+        data.failure = failure;
+
+        Ok(data)
+    }
+}
+
+impl Definition for CaptionTable {
+    fn validate(&self) -> Result<(), String> {
+        for (path, caption) in &self.map {
+            caption
+                .validate()
+                .map_err(|error| format!("\"{path}\": {error}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// A caption on screen, or waiting to be.
+struct CaptionEntry {
+    caption: Caption,
+    /// Time left on screen, in seconds.
+    time: f32,
+    /// Push order, so that captions of equal priority are shown first-in, first-out.
+    order: usize,
+}
+
+/// Caption queue. Captions are pushed when a sound is played, and shown a few lines at a time, by
+/// priority.
+#[derive(Default)]
+pub struct CaptionQueue {
+    /// Caption table for the loaded idiom. Loaded on the first update, and again on idiom change.
+    table: Option<(IdiomKind, CaptionTable)>,
+    /// Sound paths played since the last update.
+    pending: Vec<String>,
+    /// Captions waiting for a free line.
+    wait_list: Vec<CaptionEntry>,
+    /// Captions on screen, from top to bottom.
+    show_list: Vec<CaptionEntry>,
+    /// Push count, for the push order of each caption.
+    order: usize,
+    /// Failure list since the last call to "take_failure".
+    failure: Vec<String>,
+}
+
+impl CaptionQueue {
+    /// Maximum caption count on screen at a time.
+    const LINE_COUNT: usize = 3;
+    /// Maximum caption count waiting for a free line. The oldest caption of the lowest priority is
+    /// dropped first.
+    const WAIT_COUNT: usize = 8;
+
+    /// Push the caption for a sound, if the sound has one.
+    pub fn push_sound(&mut self, path: &str) {
+        self.pending.push(path.to_string());
+    }
+
+    /// Push a caption.
+    pub fn push(&mut self, caption: Caption) {
+        // a caption already on screen or waiting, such as a repeating sound, is only refreshed.
+        if let Some(entry) = self
+            .show_list
+            .iter_mut()
+            .chain(self.wait_list.iter_mut())
+            .find(|entry| entry.caption.text == caption.text)
+        {
+            entry.time = entry.time.max(caption.get_length());
+            return;
+        }
+
+        self.wait_list.push(CaptionEntry {
+            time: caption.get_length(),
+            caption,
+            order: self.order,
+        });
+
+        self.order += 1;
+
+        if self.wait_list.len() > Self::WAIT_COUNT
+            && let Some(index) = self
+                .wait_list
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| (entry.caption.priority, entry.order))
+                .map(|(i, _)| i)
+        {
+            self.wait_list.remove(index);
+        }
+    }
+
+    /// Clear every caption.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.wait_list.clear();
+        self.show_list.clear();
+    }
+
+    /// Take every failure since the last call, such as a caption table that could not be loaded.
+    pub fn take_failure(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.failure)
+    }
+
+    /// Advance every caption on screen, and fill any free line from the wait list. A caption table
+    /// that could not be loaded is replaced by an empty one, and the failure is kept for
+    /// "take_failure".
+    pub fn update(&mut self, user: &User, time: f32) {
+        if self
+            .table
+            .as_ref()
+            .is_none_or(|(idiom, _)| *idiom != user.video_idiom)
+        {
+            let table = match CaptionTable::new(user.video_idiom) {
+                Ok(mut table) => {
+                    self.failure.append(&mut table.failure);
+                    table
+                }
+                Err(error) => {
+                    self.failure.push(format!(
+                        "CaptionQueue::update(): Could not load caption table: {error}"
+                    ));
+                    CaptionTable::default()
+                }
+            };
+
+            self.table = Some((user.video_idiom, table));
+        }
+
+        if let Some((_, table)) = &self.table {
+            let caption_list: Vec<Caption> = self
+                .pending
+                .drain(..)
+                .filter_map(|path| table.map.get(&path).cloned())
+                .collect();
+
+            for caption in caption_list {
+                self.push(caption);
+            }
+        }
+
+        // captions the user has turned off are dropped, rather than kept hidden.
+        self.wait_list
+            .retain(|entry| entry.caption.is_visible(user));
+        self.show_list
+            .retain(|entry| entry.caption.is_visible(user));
+
+        for entry in &mut self.show_list {
+            entry.time -= time;
+        }
+
+        self.show_list.retain(|entry| entry.time > 0.0);
+
+        while !self.wait_list.is_empty() {
+            let Some(index) = self
+                .wait_list
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, entry)| (entry.caption.priority, std::cmp::Reverse(entry.order)))
+                .map(|(i, _)| i)
+            else {
+                break;
+            };
+
+            if self.show_list.len() >= Self::LINE_COUNT {
+                // a full screen only gives way to a caption of a higher priority than its lowest.
+                let Some(lowest) = self
+                    .show_list
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, entry)| (entry.caption.priority, entry.order))
+                    .map(|(i, _)| i)
+                else {
+                    break;
+                };
+
+                if self.show_list[lowest].caption.priority >= self.wait_list[index].caption.priority
+                {
+                    break;
+                }
+
+                self.show_list.remove(lowest);
+            }
+
+            let entry = self.wait_list.remove(index);
+            self.show_list.push(entry);
+        }
+    }
+
+    /// Get every caption on screen, from top to bottom.
+    pub fn get_show_list(&self) -> impl Iterator<Item = &Caption> {
+        self.show_list.iter().map(|entry| &entry.caption)
+    }
+}
@@ -0,0 +1,310 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::camera::*;
+use crate::timeline::Fade;
+use crate::view::*;
+use crate::world::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// An off-screen capture of a level, from a fixed camera pose.
+#[derive(Debug, Deserialize, Clone)]
+pub struct Capture {
+    /// Level folder name, in "data/level".
+    pub level: String,
+    /// Camera point.
+    pub point: Vector3,
+    /// Camera focus.
+    pub focus: Vector3,
+    /// Camera field of view.
+    #[serde(default = "Capture::field_default")]
+    pub field: f32,
+    /// Draw the main menu over the level, rather than the in-game interface alone.
+    #[serde(default)]
+    pub menu: bool,
+}
+
+impl Capture {
+    /// Frame count to draw before the capture, so that every per-frame state (shadow budget,
+    /// environment blend...) has settled.
+    const FRAME_WARM: usize = 4;
+    /// Usage for the capture command line.
+    const USAGE: &str =
+        "Usage: engine --capture {level} {point x,y,z} {focus x,y,z} {output path} [--menu]";
+
+    fn field_default() -> f32 {
+        90.0
+    }
+
+    /// Render the level to an image. The app must be made with `App::new_fixed`, so that the
+    /// local user file can not change the result, and be initialized with the given context.
+    pub fn render(&self, app: &mut App, context: &mut Context) -> anyhow::Result<Image> {
+        // level data is only loaded with the tutorial flag set.
+        app.user.tutorial = true;
+        app.view.layout = self.menu.then_some(Layout::Main);
+
+        let mut world = World::new_level(app, context, &self.level)?;
+        let ctx = context as *mut Context;
+
+        // the level fade-in is only advanced by a tick, and a capture only draws.
+        world.fade = Fade::new(0.0, 0.0, 0.0);
+
+        // a fixed source above every other, so that entities can not move the camera.
+        world.scene.camera.attach(
+            CameraSource::Fixed {
+                pose: CameraPose::new(self.point, self.focus, Vector3::up(), self.field),
+            },
+            CameraSystem::PRIORITY_FREE,
+            0.0,
+        );
+
+        for frame in 0..=Self::FRAME_WARM {
+            unsafe {
+                let mut draw = context.handle.begin_drawing(&context.thread);
+
+                draw.clear_background(Color::BLACK);
+
+                // the main menu pauses the world, as it does in game.
+                world.draw(app, &mut draw, &mut *ctx, self.menu)?;
+
+                View::draw_layout(app, &mut draw, &mut *ctx)?;
+
+                if frame == Self::FRAME_WARM {
+                    ffi::rlDrawRenderBatchActive();
+
+                    return Ok(draw.load_image_from_screen(&(*ctx).thread));
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// Run the capture command line: render a level and camera pose to an image file, with a
+    /// hidden window.
+    pub fn main(argument: &[String]) -> anyhow::Result<()> {
+        let (argument, menu) = match argument {
+            [argument @ .., last] if last == "--menu" => (argument, true),
+            _ => (argument, false),
+        };
+
+        let [level, point, focus, path] = argument else {
+            return Err(anyhow::Error::msg(Self::USAGE));
+        };
+
+        let capture = Self {
+            level: level.to_string(),
+            point: Self::parse_vector(point)?,
+            focus: Self::parse_vector(focus)?,
+            field: Self::field_default(),
+            menu,
+        };
+
+        let mut context = Context::new_hidden(1024, 768)?;
+        let mut app = App::new_fixed();
+
+        unsafe {
+            let context = &mut context as *mut Context;
+            app.initialize(&mut *context)?;
+        };
+
+        let image = capture.render(&mut app, &mut context)?;
+
+        if !image.export_image(path) {
+            return Err(anyhow::Error::msg(format!(
+                "Capture::main(): Could not write image \"{path}\"."
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Parse a vector from a "x,y,z" string.
+    fn parse_vector(text: &str) -> anyhow::Result<Vector3> {
+        let list = text
+            .split(',')
+            .map(|value| value.trim().parse::<f32>())
+            .collect::<Result<Vec<f32>, _>>()?;
+
+        if let [x, y, z] = list[..] {
+            Ok(Vector3::new(x, y, z))
+        } else {
+            Err(anyhow::Error::msg(format!(
+                "Capture::parse_vector(): Invalid vector \"{text}\". {}",
+                Self::USAGE
+            )))
+        }
+    }
+}
+
+//================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use hashbrown::HashMap;
+
+    /// Path to the golden image case list, by case name.
+    const PATH_CASE: &str = "data/test/golden.json";
+    /// Path to the golden image folder.
+    const PATH_GOLDEN: &str = "data/test/golden";
+    /// Path to the output folder, for every failed case.
+    const PATH_OUTPUT: &str = "data/test/output";
+    /// Maximum per-channel difference for a pixel to still match.
+    const TOLERANCE_CHANNEL: u8 = 8;
+    /// Maximum fraction of mismatched pixels for an image to still match.
+    const TOLERANCE_IMAGE: f32 = 0.005;
+
+    /// Get the fraction of mismatched pixels between two images.
+    fn compare(a: &Image, b: &Image) -> f32 {
+        if a.width != b.width || a.height != b.height {
+            return 1.0;
+        }
+
+        let a = a.get_image_data();
+        let b = b.get_image_data();
+        let mismatch = a
+            .iter()
+            .zip(b.iter())
+            .filter(|(a, b)| {
+                a.r.abs_diff(b.r) > TOLERANCE_CHANNEL
+                    || a.g.abs_diff(b.g) > TOLERANCE_CHANNEL
+                    || a.b.abs_diff(b.b) > TOLERANCE_CHANNEL
+            })
+            .count();
+
+        mismatch as f32 / a.len().max(1) as f32
+    }
+
+    /// Render every golden image case, and compare it against its golden image. Set
+    /// "GOLDEN_UPDATE=1" to write every golden image instead.
+    ///
+    /// Every case is rendered in a single test, as the window and GL context can only be created
+    /// once per process. The test needs a GL context and a display, so it only runs with
+    /// "GOLDEN_CAPTURE=1", which CI must set. On a machine with no GPU, CI runs it with:
+    ///
+    /// GOLDEN_CAPTURE=1 LIBGL_ALWAYS_SOFTWARE=1 xvfb-run -s "-screen 0 1024x768x24" cargo test
+    ///
+    /// Golden images must be written with the same software renderer, or the tolerance will not
+    /// hold. User data is fixed, and "user.json" is never read nor written.
+    #[test]
+    fn golden_image() -> anyhow::Result<()> {
+        if !std::env::var("GOLDEN_CAPTURE").is_ok_and(|value| value == "1") {
+            eprintln!("golden_image: skipped, set \"GOLDEN_CAPTURE=1\" to run.");
+            return Ok(());
+        }
+
+        // data paths are relative to the workspace, not to the engine package.
+        std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))?;
+
+        let file = std::fs::read_to_string(PATH_CASE).map_err(|error| {
+            anyhow::Error::msg(format!("Could not read case list \"{PATH_CASE}\": {error}"))
+        })?;
+        let case_list: HashMap<String, Capture> = serde_json::from_str(&file)?;
+
+        assert!(!case_list.is_empty(), "Case list \"{PATH_CASE}\" is empty.");
+        let update = std::env::var("GOLDEN_UPDATE").is_ok_and(|value| value == "1");
+
+        let mut context = Context::new_hidden(1024, 768)?;
+        let mut failure = Vec::default();
+
+        std::fs::create_dir_all(PATH_OUTPUT)?;
+
+        for (name, capture) in &case_list {
+            let mut app = App::new_fixed();
+
+            unsafe {
+                let context = &mut context as *mut Context;
+                app.initialize(&mut *context)?;
+            };
+
+            let image = capture.render(&mut app, &mut context)?;
+            let golden = format!("{PATH_GOLDEN}/{name}.png");
+
+            if update {
+                image.export_image(&golden);
+                continue;
+            }
+
+            let Ok(golden) = Image::load_image(&golden) else {
+                image.export_image(&format!("{PATH_OUTPUT}/{name}.png"));
+                failure.push(format!(
+                    "{name}: no golden image at \"{golden}\", run with \"GOLDEN_UPDATE=1\""
+                ));
+                continue;
+            };
+
+            let difference = compare(&image, &golden);
+
+            if difference > TOLERANCE_IMAGE {
+                image.export_image(&format!("{PATH_OUTPUT}/{name}.png"));
+                failure.push(format!(
+                    "{name}: {:.2}% of pixels differ",
+                    difference * 100.0
+                ));
+            }
+        }
+
+        assert!(
+            failure.is_empty(),
+            "Golden image mismatch (output in \"{PATH_OUTPUT}\"):\n{}",
+            failure.join("\n")
+        );
+
+        Ok(())
+    }
+}
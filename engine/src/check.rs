@@ -0,0 +1,489 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+// Offline asset validation tool. Does not open a window. Run from the folder holding "data".
+
+use engine::asset::*;
+use engine::camera::*;
+use engine::caption::*;
+use engine::decal::*;
+use engine::helper::*;
+use engine::idiom::*;
+use engine::particle::*;
+use engine::timeline::*;
+use engine::user::*;
+use engine::world::*;
+
+//================================================================
+
+use hashbrown::HashSet;
+use std::path::{Path, PathBuf};
+
+//================================================================
+
+/// Validation report.
+#[derive(Default)]
+struct Report {
+    /// Every failure found, in order.
+    failure: Vec<String>,
+    /// Every file that was validated.
+    file: HashSet<PathBuf>,
+}
+
+impl Report {
+    const PATH_DATA: &str = "data";
+    const PATH_LEVEL: &str = "data/level";
+    const PATH_PARTICLE: &str = "data/particle";
+    const PATH_DECAL: &str = "data/decal";
+    const PATH_CAMERA: &str = "data/camera";
+    const PATH_TIMELINE: &str = "data/timeline";
+    const PATH_CAPTION: &str = "data/caption";
+    const PATH_IDIOM: &str = "data/idiom";
+
+    fn fail(&mut self, source: &str, text: &str) {
+        let text = format!("{source}: {text}");
+        println!("  FAIL {text}");
+        self.failure.push(text);
+    }
+
+    /// Check that a file is present, and keep it as validated if so.
+    fn check_file(&mut self, source: &str, path: &Path) -> bool {
+        if path.is_file() {
+            self.file.insert(path.to_path_buf());
+            true
+        } else {
+            self.fail(source, &format!("Missing file \"{}\".", path.display()));
+            false
+        }
+    }
+
+    /// Check every level folder in the level directory.
+    fn check_level_list(&mut self) -> anyhow::Result<()> {
+        let mut list: Vec<PathBuf> = std::fs::read_dir(Self::PATH_LEVEL)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect();
+
+        list.sort();
+
+        for path in list {
+            self.check_level(&path);
+        }
+
+        Ok(())
+    }
+
+    /// Check a level folder: its level file, every room model, and every entity.
+    fn check_level(&mut self, folder: &Path) {
+        let name = folder
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let path = folder.join(format!("{name}.json"));
+        let source = path.display().to_string();
+
+        println!("level \"{name}\"");
+
+        if !self.check_file(&source, &path) {
+            return;
+        }
+
+        let level = match Level::new(&path.to_string_lossy()) {
+            Ok(level) => level,
+            Err(error) => {
+                self.fail(&source, &format!("Could not parse level: {error}"));
+                return;
+            }
+        };
+
+        for room in &level.level {
+            self.check_model(&folder.join(room));
+        }
+    }
+
+    /// Check a model file, its sidecar meta file, and every file either of them point to.
+    fn check_model(&mut self, path: &Path) {
+        let source = path.display().to_string();
+
+        if !self.check_file(&source, path) {
+            return;
+        }
+
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let json = match extension.as_str() {
+            "glb" => std::fs::read(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| Self::parse_glb(&file)),
+            "gltf" => std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| Ok(serde_json::from_str(&file)?)),
+            _ => Ok(serde_json::Value::Null),
+        };
+
+        match json {
+            Ok(json) => {
+                // Check every external buffer and image the model is pointing to.
+                let folder = path.parent().unwrap_or(Path::new(""));
+
+                for key in ["buffers", "images"] {
+                    if let Some(list) = json.get(key).and_then(|list| list.as_array()) {
+                        for entry in list {
+                            if let Some(uri) = entry.get("uri").and_then(|uri| uri.as_str())
+                                && !uri.starts_with("data:")
+                            {
+                                self.check_file(&source, &folder.join(uri));
+                            }
+                        }
+                    }
+                }
+            }
+            Err(error) => self.fail(&source, &format!("Could not parse model: {error}")),
+        }
+
+        self.check_meta(path);
+    }
+
+    /// Check a model's sidecar meta file, if any.
+    fn check_meta(&mut self, path: &Path) {
+        let path = path.with_extension(ModelMeta::FILE_EXTENSION);
+        let source = path.display().to_string();
+
+        // a meta file is checked once, whether it is found through a level room or the sweep.
+        if !path.is_file() || self.file.contains(&path) {
+            return;
+        }
+
+        self.file.insert(path.clone());
+
+        let meta = match ModelMeta::new(&path.to_string_lossy()) {
+            Ok(meta) => meta,
+            Err(error) => {
+                self.fail(&source, &format!("Could not parse meta: {error}"));
+                return;
+            }
+        };
+
+        if let Some(texture) = &meta.texture {
+            for texture in texture {
+                self.check_file(&source, Path::new(&ModelMeta::get_data_path(texture)));
+            }
+        }
+
+        if let Some(material) = &meta.material {
+            for material in material.values() {
+                let map_list = [
+                    &material.albedo,
+                    &material.normal,
+                    &material.emissive,
+                    &material.roughness,
+                ];
+
+                for texture in map_list.into_iter().flatten() {
+                    self.check_file(&source, Path::new(&ModelMeta::get_data_path(texture)));
+                }
+            }
+        }
+
+        if let Some(graph) = &meta.graph
+            && let Err(error) = graph.validate()
+        {
+            self.fail(&source, &format!("Invalid animation graph: {error}"));
+        }
+
+        if let Some(level) = &meta.level {
+            for entry in level {
+                self.check_file(&source, Path::new(&entry.path));
+            }
+        }
+
+        if let Err(error) = ModelLevel::validate(
+            meta.level.as_deref().unwrap_or_default(),
+            meta.fade.as_ref(),
+        ) {
+            self.fail(&source, &format!("Invalid level of detail: {error}"));
+        }
+
+        if let Some(event) = &meta.event {
+            for frame in event.values() {
+                for event in frame.values() {
+                    match event {
+                        AnimationEvent::Sound { path } => {
+                            self.check_file(&source, Path::new(&ModelMeta::get_data_path(path)));
+                        }
+                        AnimationEvent::Particle { name } => {
+                            self.check_file(&source, Path::new(&ParticleData::get_path(name)));
+                        }
+                        AnimationEvent::Custom(_) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check an atlas file, or the folder of loose image files it falls back to if missing.
+    fn check_atlas(&mut self, source: &str, path: &Path) {
+        let folder = path.with_extension("");
+
+        if path.is_file() || !folder.is_dir() {
+            self.check_file(source, path);
+            return;
+        }
+
+        if let Ok(list) = std::fs::read_dir(&folder) {
+            for entry in list.flatten() {
+                let path = entry.path();
+
+                if path.extension().is_some_and(|extension| extension == "png") {
+                    self.file.insert(path);
+                }
+            }
+        }
+    }
+
+    /// Check every definition file (particle, decal) in a definition directory.
+    fn check_definition_list(
+        &mut self,
+        folder: &str,
+        check: fn(&mut Self, &Path),
+    ) -> anyhow::Result<()> {
+        let folder = Path::new(folder);
+
+        if !folder.is_dir() {
+            return Ok(());
+        }
+
+        for entry in std::fs::read_dir(folder)? {
+            let path = entry?.path();
+
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                check(self, &path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that a definition file is present, can be parsed, and is valid.
+    fn check_definition<T: Definition>(&mut self, path: &Path) -> Option<T> {
+        let source = path.display().to_string();
+
+        if !self.check_file(&source, path) {
+            return None;
+        }
+
+        match T::load(&path.to_string_lossy()) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                self.fail(&source, &error.to_string());
+                None
+            }
+        }
+    }
+
+    /// Check a particle definition, and its atlas file.
+    fn check_particle(&mut self, path: &Path) {
+        if let Some(data) = self.check_definition::<ParticleData>(path) {
+            self.check_atlas(&path.display().to_string(), Path::new(&data.atlas));
+        }
+    }
+
+    /// Check a decal definition, and its atlas file.
+    fn check_decal(&mut self, path: &Path) {
+        if let Some(data) = self.check_definition::<DecalData>(path) {
+            self.check_atlas(&path.display().to_string(), Path::new(&data.atlas));
+        }
+    }
+
+    /// Check a camera path.
+    fn check_camera(&mut self, path: &Path) {
+        self.check_definition::<CameraPath>(path);
+    }
+
+    /// Check a timeline, and every camera path and sound it uses.
+    fn check_timeline(&mut self, path: &Path) {
+        let source = path.display().to_string();
+        let Some(data) = self.check_definition::<TimelineData>(path) else {
+            return;
+        };
+
+        for key in &data.camera {
+            self.check_file(&source, Path::new(&CameraPath::get_path(&key.path)));
+        }
+
+        for key in &data.sound {
+            self.check_file(&source, Path::new(&key.path));
+        }
+    }
+
+    /// Check a caption table, and every sound it has a caption for.
+    fn check_caption(&mut self, path: &Path) {
+        let source = path.display().to_string();
+        let Some(data) = self.check_definition::<CaptionTable>(path) else {
+            return;
+        };
+
+        for sound in data.map.keys() {
+            self.check_file(&source, Path::new(sound));
+        }
+    }
+
+    /// Check a string table, and for any key the English table does not have.
+    fn check_idiom(&mut self, path: &Path) {
+        let source = path.display().to_string();
+        let Some(data) = self.check_definition::<IdiomTable>(path) else {
+            return;
+        };
+
+        let english = IdiomTable::get_path(IdiomKind::English);
+
+        if path == Path::new(&english) {
+            return;
+        }
+
+        // a broken English table is reported on its own.
+        let Ok(english) = IdiomTable::load(&english) else {
+            return;
+        };
+
+        let mut list: Vec<&String> = data
+            .map
+            .keys()
+            .filter(|key| !english.map.contains_key(*key))
+            .collect();
+
+        list.sort();
+
+        for key in list {
+            self.fail(&source, &format!("Unknown key \"{key}\"."));
+        }
+    }
+
+    /// Check every meta file in the data directory, including those not used by a level room.
+    fn check_meta_list(&mut self, folder: &Path) -> anyhow::Result<()> {
+        for entry in std::fs::read_dir(folder)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                self.check_meta_list(&path)?;
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == ModelMeta::FILE_EXTENSION)
+            {
+                self.check_meta(&path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the JSON chunk out of a binary glTF file.
+    fn parse_glb(file: &[u8]) -> anyhow::Result<serde_json::Value> {
+        let read = |index: usize| -> anyhow::Result<u32> {
+            let data = file
+                .get(index..index + 4)
+                .ok_or(anyhow::Error::msg("Unexpected end of file."))?;
+            Ok(u32::from_le_bytes(data.try_into()?))
+        };
+
+        if file.get(0..4) != Some(&b"glTF"[..]) {
+            return Err(anyhow::Error::msg("Not a binary glTF file."));
+        }
+
+        if read(4)? != 2 {
+            return Err(anyhow::Error::msg("Unsupported glTF version."));
+        }
+
+        let length = read(12)? as usize;
+
+        if file.get(16..20) != Some(&b"JSON"[..]) {
+            return Err(anyhow::Error::msg("First chunk is not a JSON chunk."));
+        }
+
+        let chunk = file
+            .get(20..20 + length)
+            .ok_or(anyhow::Error::msg("Unexpected end of file."))?;
+
+        Ok(serde_json::from_slice(chunk)?)
+    }
+}
+
+//================================================================
+
+fn main() -> anyhow::Result<()> {
+    let mut report = Report::default();
+
+    report.check_level_list()?;
+    report.check_meta_list(Path::new(Report::PATH_DATA))?;
+    report.check_definition_list(Report::PATH_PARTICLE, Report::check_particle)?;
+    report.check_definition_list(Report::PATH_DECAL, Report::check_decal)?;
+    report.check_definition_list(Report::PATH_CAMERA, Report::check_camera)?;
+    report.check_definition_list(Report::PATH_TIMELINE, Report::check_timeline)?;
+    report.check_definition_list(Report::PATH_CAPTION, Report::check_caption)?;
+    report.check_definition_list(Report::PATH_IDIOM, Report::check_idiom)?;
+
+    println!(
+        "{} file(s) checked, {} failure(s).",
+        report.file.len(),
+        report.failure.len()
+    );
+
+    if !report.failure.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
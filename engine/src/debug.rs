@@ -0,0 +1,355 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use hashbrown::HashSet;
+use raylib::prelude::*;
+use std::fmt::Display;
+
+//================================================================
+
+/// Debug draw category. Each category can be toggled from the console.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DebugCategory {
+    General,
+    Physical,
+    Entity,
+    Light,
+    Weapon,
+    Path,
+}
+
+impl DebugCategory {
+    /// Every category.
+    pub const LIST: [Self; 6] = [
+        Self::General,
+        Self::Physical,
+        Self::Entity,
+        Self::Light,
+        Self::Weapon,
+        Self::Path,
+    ];
+
+    /// Get a category from its (lower-case) name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::LIST
+            .into_iter()
+            .find(|category| category.to_string().to_lowercase() == name.to_lowercase())
+    }
+}
+
+impl Display for DebugCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::General => "General",
+            Self::Physical => "Physical",
+            Self::Entity => "Entity",
+            Self::Light => "Light",
+            Self::Weapon => "Weapon",
+            Self::Path => "Path",
+        };
+
+        f.write_str(string)
+    }
+}
+
+//================================================================
+
+enum DebugShape {
+    Line {
+        a: Vector3,
+        b: Vector3,
+    },
+    Arrow {
+        a: Vector3,
+        b: Vector3,
+    },
+    Cuboid {
+        point: Vector3,
+        shape: Vector3,
+    },
+    Sphere {
+        point: Vector3,
+        radius: f32,
+    },
+    Ray {
+        point: Vector3,
+        direction: Vector3,
+        hit: Option<Vector3>,
+    },
+    Text {
+        point: Vector3,
+        text: String,
+    },
+}
+
+struct DebugItem {
+    shape: DebugShape,
+    color: Color,
+    category: DebugCategory,
+    /// Time (in seconds) left to draw the item for.
+    life: f32,
+}
+
+/// Immediate-mode debug draw. Items can be pushed from anywhere (including "tick"), and are drawn
+/// on every frame until their life-time is over. An item with no life-time is kept until the next
+/// tick, so it is drawn exactly as long as it would be if pushed on every tick.
+#[derive(Default)]
+pub struct DebugDraw {
+    item_list: Vec<DebugItem>,
+    /// Enabled category list. Items pushed for a disabled category are discarded.
+    enable: HashSet<DebugCategory>,
+}
+
+impl DebugDraw {
+    /// Arrow head length, relative to the arrow length.
+    const ARROW_HEAD: f32 = 0.2;
+    /// Sphere radius for a ray hit point.
+    const HIT_RADIUS: f32 = 0.05;
+    /// Text size, in pixels.
+    const TEXT_SIZE: i32 = 16;
+
+    /// Age every item, and remove every item whose life-time is over. Must be called at the start
+    /// of every tick, with the enabled category list.
+    pub fn tick(&mut self, enable: &HashSet<DebugCategory>, time: f32) {
+        if self.enable != *enable {
+            self.enable = enable.clone();
+        }
+
+        for item in &mut self.item_list {
+            item.life -= time;
+        }
+
+        self.item_list
+            .retain(|item| item.life > 0.0 && self.enable.contains(&item.category));
+    }
+
+    /// Check if a category is enabled, to skip building expensive debug data.
+    pub fn has_category(&self, category: DebugCategory) -> bool {
+        self.enable.contains(&category)
+    }
+
+    /// Draw a line.
+    pub fn line(
+        &mut self,
+        category: DebugCategory,
+        a: Vector3,
+        b: Vector3,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(category, DebugShape::Line { a, b }, color, life);
+    }
+
+    /// Draw an arrow, from "a" pointing to "b".
+    pub fn arrow(
+        &mut self,
+        category: DebugCategory,
+        a: Vector3,
+        b: Vector3,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(category, DebugShape::Arrow { a, b }, color, life);
+    }
+
+    /// Draw a wire-frame cuboid, with a given half-extent.
+    pub fn cuboid(
+        &mut self,
+        category: DebugCategory,
+        point: Vector3,
+        shape: Vector3,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(category, DebugShape::Cuboid { point, shape }, color, life);
+    }
+
+    /// Draw a wire-frame sphere.
+    pub fn sphere(
+        &mut self,
+        category: DebugCategory,
+        point: Vector3,
+        radius: f32,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(category, DebugShape::Sphere { point, radius }, color, life);
+    }
+
+    /// Draw a ray, up to a hit point if any.
+    pub fn ray(
+        &mut self,
+        category: DebugCategory,
+        point: Vector3,
+        direction: Vector3,
+        hit: Option<Vector3>,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(
+            category,
+            DebugShape::Ray {
+                point,
+                direction,
+                hit,
+            },
+            color,
+            life,
+        );
+    }
+
+    /// Draw a text label at a world point.
+    pub fn text(
+        &mut self,
+        category: DebugCategory,
+        point: Vector3,
+        text: &str,
+        color: Color,
+        life: f32,
+    ) {
+        self.push(
+            category,
+            DebugShape::Text {
+                point,
+                text: text.to_string(),
+            },
+            color,
+            life,
+        );
+    }
+
+    fn push(&mut self, category: DebugCategory, shape: DebugShape, color: Color, life: f32) {
+        if !self.enable.contains(&category) {
+            return;
+        }
+
+        self.item_list.push(DebugItem {
+            shape,
+            color,
+            category,
+            // an item with no life-time must survive the next tick's aging.
+            life: life.max(f32::EPSILON),
+        });
+    }
+
+    /// Draw every 3D item.
+    pub fn draw_3d(&self, draw: &mut impl RaylibDraw3D) {
+        for item in &self.item_list {
+            match &item.shape {
+                DebugShape::Line { a, b } => draw.draw_line_3d(*a, *b, item.color),
+                DebugShape::Arrow { a, b } => {
+                    let length = (*b - *a).length();
+                    let head = *b - (*b - *a) * Self::ARROW_HEAD;
+
+                    draw.draw_line_3d(*a, head, item.color);
+                    draw.draw_cylinder_ex(
+                        head,
+                        *b,
+                        length * Self::ARROW_HEAD * 0.25,
+                        0.0,
+                        8,
+                        item.color,
+                    );
+                }
+                DebugShape::Cuboid { point, shape } => draw.draw_cube_wires(
+                    *point,
+                    shape.x * 2.0,
+                    shape.y * 2.0,
+                    shape.z * 2.0,
+                    item.color,
+                ),
+                DebugShape::Sphere { point, radius } => {
+                    draw.draw_sphere_wires(*point, *radius, 8, 8, item.color)
+                }
+                DebugShape::Ray {
+                    point,
+                    direction,
+                    hit,
+                } => match hit {
+                    Some(hit) => {
+                        draw.draw_line_3d(*point, *hit, item.color);
+                        draw.draw_sphere(*hit, Self::HIT_RADIUS, item.color);
+                    }
+                    None => draw.draw_line_3d(*point, *point + *direction, item.color),
+                },
+                DebugShape::Text { .. } => {}
+            }
+        }
+    }
+
+    /// Draw every text label, projected to the screen with a given camera.
+    pub fn draw_2d(&self, draw: &mut RaylibMode2D<'_, RaylibDrawHandle<'_>>, camera: Camera3D) {
+        let forward = camera.target - camera.position;
+
+        for item in &self.item_list {
+            if let DebugShape::Text { point, text } = &item.shape {
+                // skip every label behind the camera.
+                if (*point - camera.position).dot(forward) <= 0.0 {
+                    continue;
+                }
+
+                let screen = draw.get_world_to_screen(*point, camera);
+                // "draw_text" uses the default font, with a spacing of a tenth of the text size.
+                let width = draw
+                    .get_font_default()
+                    .measure_text(text, Self::TEXT_SIZE as f32, Self::TEXT_SIZE as f32 / 10.0)
+                    .x as i32;
+
+                draw.draw_text(
+                    text,
+                    screen.x as i32 - width / 2,
+                    screen.y as i32,
+                    Self::TEXT_SIZE,
+                    item.color,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,555 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::asset::*;
+use crate::helper::*;
+
+//================================================================
+
+use hashbrown::{HashMap, HashSet};
+use rand::Rng;
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// Decal definition, from a "data/decal/*.json" file.
+#[derive(Debug, Deserialize, Clone)]
+pub struct DecalData {
+    /// Path to the atlas file.
+    pub atlas: String,
+    /// Atlas frame list. A random frame is picked for each decal.
+    pub frame: Vec<String>,
+    /// Size range, in world units.
+    pub size: [f32; 2],
+    /// Projection depth, in front of and behind the hit surface.
+    #[serde(default = "DecalData::depth_default")]
+    pub depth: f32,
+    /// Life-time, in seconds. Zero will keep the decal until it is overwritten.
+    #[serde(default)]
+    pub life: f32,
+    /// Fade-out length, in seconds, at the end of the life-time.
+    #[serde(default)]
+    pub fade: f32,
+    /// Decal tint.
+    #[serde(default = "DecalData::color_default")]
+    pub color: Color,
+}
+
+impl DecalData {
+    /// Path to the decal definition folder.
+    const PATH: &str = "data/decal";
+
+    fn depth_default() -> f32 {
+        0.25
+    }
+
+    fn color_default() -> Color {
+        Color::WHITE
+    }
+
+    /// Load a decal definition by name.
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        Self::load(&Self::get_path(name))
+    }
+
+    /// Get the path to a decal definition by name.
+    pub fn get_path(name: &str) -> String {
+        format!("{}/{name}.json", Self::PATH)
+    }
+}
+
+impl Definition for DecalData {
+    fn validate(&self) -> Result<(), String> {
+        if self.frame.is_empty() {
+            return Err("Frame list is empty.".to_string());
+        }
+
+        if self.size[0] <= 0.0 || self.size[1] < self.size[0] {
+            return Err("Size range must be positive and ordered.".to_string());
+        }
+
+        if self.depth <= 0.0 {
+            return Err("Depth must be positive.".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// A projected decal, clipped against room geometry.
+struct Decal {
+    /// Path to the atlas file.
+    atlas: String,
+    /// Atlas frame name.
+    frame: String,
+    /// Triangle list, with a world point and an atlas texture coordinate for each vertex.
+    vertex: Vec<(Vector3, Vector2)>,
+    color: Color,
+    /// Time (in seconds) since the decal was spawned.
+    time: f32,
+    /// Life-time, in seconds. Zero if the decal never expires.
+    life: f32,
+    /// Fade-out length, in seconds.
+    fade: f32,
+}
+
+impl Decal {
+    /// Get the decal alpha, from the fade-out at the end of the life-time.
+    fn get_alpha(&self) -> f32 {
+        if self.life <= 0.0 || self.fade <= 0.0 {
+            1.0
+        } else {
+            ((self.life - self.time) / self.fade).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Decal system. Decals are kept in a fixed-size ring buffer, where the oldest decal is overwritten
+/// first once the buffer is full. Level-placed decals are kept apart, and are never overwritten.
+#[derive(Default)]
+pub struct DecalSystem {
+    /// Decal definition, by name. Definitions are loaded on the first update after a decal using
+    /// them is spawned.
+    data: HashMap<String, DecalData>,
+    /// Decal definition that could not be loaded, by name. Decals using them are skipped.
+    miss: HashSet<String>,
+    /// Failure list since the last call to "take_failure".
+    failure: Vec<String>,
+    /// Decals spawned since the last update, with their name, point, surface normal, and whether
+    /// they are level-placed.
+    pending: Vec<(String, Vector3, Vector3, bool)>,
+    /// Decal slot list.
+    decal_list: Vec<Option<Decal>>,
+    /// Level-placed decal list.
+    fixed_list: Vec<Decal>,
+    /// Next slot to write to.
+    index: usize,
+}
+
+impl DecalSystem {
+    /// Maximum decal count.
+    const DECAL_COUNT: usize = 256;
+    /// Distance the decal is pushed off the surface, to avoid depth fighting.
+    const SURFACE_OFFSET: f32 = 0.005;
+    /// Minimum facing between a triangle and the decal normal for the triangle to receive the decal.
+    const SURFACE_FACING: f32 = 0.1;
+
+    /// Spawn a decal from a definition name, at a point on a surface with a given normal.
+    pub fn spawn(&mut self, name: &str, point: Vector3, normal: Vector3) {
+        self.pending.push((name.to_string(), point, normal, false));
+    }
+
+    /// Spawn a level-placed decal, which is kept out of the ring buffer so that other decals can
+    /// not overwrite it.
+    pub fn spawn_fixed(&mut self, name: &str, point: Vector3, normal: Vector3) {
+        self.pending.push((name.to_string(), point, normal, true));
+    }
+
+    /// Clear every decal.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.decal_list.clear();
+        self.fixed_list.clear();
+        self.index = 0;
+    }
+
+    /// Take every failure since the last call, such as a decal definition that could not be
+    /// loaded.
+    pub fn take_failure(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.failure)
+    }
+
+    /// Load any missing decal definition, project every pending decal against a room list (a model
+    /// path and bound for each room), and age every decal. A decal that could not be loaded or
+    /// projected is skipped, and the failure is kept for "take_failure".
+    pub fn update(
+        &mut self,
+        asset: &mut Asset,
+        context: &mut Context,
+        room_list: &[(&str, BoundingBox)],
+        time: f32,
+    ) -> anyhow::Result<()> {
+        for (name, point, normal, fixed) in std::mem::take(&mut self.pending) {
+            if self.miss.contains(&name) {
+                continue;
+            }
+
+            if !self.data.contains_key(&name) {
+                match Self::load(asset, context, &name) {
+                    Ok(data) => {
+                        self.data.insert(name.clone(), data);
+                    }
+                    Err(error) => {
+                        self.failure.push(format!(
+                            "DecalSystem::update(): Could not load decal \"{name}\": {error}"
+                        ));
+                        self.miss.insert(name);
+                        continue;
+                    }
+                }
+            }
+
+            let data = &self.data[&name];
+            let decal = match Self::project(asset, data, room_list, point, normal) {
+                Ok(decal) => decal,
+                Err(error) => {
+                    self.failure.push(format!(
+                        "DecalSystem::update(): Could not project decal \"{name}\": {error}"
+                    ));
+                    continue;
+                }
+            };
+
+            if fixed {
+                self.fixed_list.push(decal);
+                continue;
+            }
+
+            if self.decal_list.len() < Self::DECAL_COUNT {
+                self.decal_list.resize_with(Self::DECAL_COUNT, || None);
+            }
+
+            self.decal_list[self.index] = Some(decal);
+            self.index = (self.index + 1) % Self::DECAL_COUNT;
+        }
+
+        for slot in &mut self.decal_list {
+            if let Some(decal) = slot {
+                decal.time += time;
+
+                if decal.life > 0.0 && decal.time >= decal.life {
+                    *slot = None;
+                }
+            }
+        }
+
+        for decal in &mut self.fixed_list {
+            decal.time += time;
+        }
+
+        self.fixed_list
+            .retain(|decal| decal.life <= 0.0 || decal.time < decal.life);
+
+        Ok(())
+    }
+
+    fn load(asset: &mut Asset, context: &mut Context, name: &str) -> anyhow::Result<DecalData> {
+        let data = DecalData::new(name)?;
+
+        asset.set_atlas(context, &data.atlas)?;
+
+        for frame in &data.frame {
+            asset.get_sprite(&data.atlas, frame)?;
+        }
+
+        Ok(data)
+    }
+
+    /// Draw every decal. Decals are batched by atlas texture.
+    pub fn draw(&self, _: &mut impl RaylibDraw3D, asset: &Asset) -> anyhow::Result<()> {
+        let mut batch: HashMap<&str, Vec<&Decal>> = HashMap::default();
+
+        for decal in self.decal_list.iter().flatten().chain(&self.fixed_list) {
+            batch.entry(decal.atlas.as_str()).or_default().push(decal);
+        }
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlDisableDepthMask();
+        }
+
+        let mut result = Ok(());
+
+        for (atlas, decal_list) in batch {
+            let texture = match asset.get_sprite(atlas, &decal_list[0].frame) {
+                Ok(sprite) => sprite.texture,
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            };
+
+            unsafe {
+                ffi::rlSetTexture(texture.id);
+                ffi::rlBegin(ffi::RL_TRIANGLES as i32);
+            }
+
+            result = Self::draw_atlas(asset, atlas, &decal_list, texture);
+
+            // the batch is closed, and the depth mask restored, even on failure.
+            unsafe {
+                ffi::rlEnd();
+                ffi::rlSetTexture(0);
+            }
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlEnableDepthMask();
+        }
+
+        result
+    }
+
+    /// Push every decal using an atlas into an open triangle batch.
+    fn draw_atlas<'a>(
+        asset: &'a Asset,
+        atlas: &str,
+        decal_list: &[&Decal],
+        mut texture: &'a Texture2D,
+    ) -> anyhow::Result<()> {
+        for decal in decal_list {
+            let sprite = asset.get_sprite(atlas, &decal.frame)?;
+
+            // an atlas made out of loose image files has one texture per frame.
+            if sprite.texture.id != texture.id {
+                texture = sprite.texture;
+
+                unsafe {
+                    ffi::rlEnd();
+                    ffi::rlSetTexture(texture.id);
+                    ffi::rlBegin(ffi::RL_TRIANGLES as i32);
+                }
+            }
+
+            let alpha = (decal.color.a as f32 * decal.get_alpha()) as u8;
+
+            unsafe {
+                ffi::rlColor4ub(decal.color.r, decal.color.g, decal.color.b, alpha);
+
+                for (point, coordinate) in &decal.vertex {
+                    ffi::rlTexCoord2f(coordinate.x, coordinate.y);
+                    ffi::rlVertex3f(point.x, point.y, point.z);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Project a decal box onto every room triangle it overlaps, clipping each triangle to the box.
+    fn project(
+        asset: &mut Asset,
+        data: &DecalData,
+        room_list: &[(&str, BoundingBox)],
+        point: Vector3,
+        normal: Vector3,
+    ) -> anyhow::Result<Decal> {
+        let mut random = rand::rng();
+        let size = if data.size[0] < data.size[1] {
+            random.random_range(data.size[0]..data.size[1])
+        } else {
+            data.size[0]
+        };
+        let frame = &data.frame[random.random_range(0..data.frame.len())];
+        let spin = random.random_range(0.0..std::f32::consts::TAU);
+
+        // decal space: X and Y lie on the surface, Z is the surface normal.
+        let axis_z = normal.normalized();
+        let up = if axis_z.y.abs() < 0.99 {
+            Vector3::up()
+        } else {
+            Vector3::forward()
+        };
+        let axis_x = up.cross(axis_z).normalized();
+        let axis_y = axis_z.cross(axis_x);
+        let (sin, cos) = spin.sin_cos();
+        let axis_x = axis_x * cos + axis_y * sin;
+        let axis_y = axis_z.cross(axis_x);
+
+        let shape = Vector3::new(size * 0.5, size * 0.5, data.depth);
+        let radius = shape.length();
+        let bound = BoundingBox::new(
+            point - Vector3::one() * radius,
+            point + Vector3::one() * radius,
+        );
+
+        let sprite = asset.get_sprite(&data.atlas, frame)?;
+        let texture = Vector2::new(sprite.texture.width as f32, sprite.texture.height as f32);
+        let source = sprite.shape;
+
+        let mut vertex = Vec::default();
+
+        for (path, room_bound) in room_list {
+            if !room_bound.check_collision_boxes(bound) {
+                continue;
+            }
+
+            let model = asset.get_model(path)?;
+
+            for mesh in model.model.meshes() {
+                let point_list = mesh.vertices();
+                let index_list: Vec<usize> = if mesh.indices.is_null() {
+                    (0..point_list.len()).collect()
+                } else {
+                    unsafe {
+                        std::slice::from_raw_parts(
+                            mesh.indices as *const u16,
+                            mesh.triangleCount as usize * 3,
+                        )
+                    }
+                    .iter()
+                    .map(|index| *index as usize)
+                    .collect()
+                };
+
+                for triangle in index_list.chunks_exact(3) {
+                    let a = point_list[triangle[0]];
+                    let b = point_list[triangle[1]];
+                    let c = point_list[triangle[2]];
+
+                    let facing = (b - a).cross(c - a).normalized().dot(axis_z);
+
+                    if facing < Self::SURFACE_FACING {
+                        continue;
+                    }
+
+                    // move into decal space, then clip against each of the six box planes.
+                    let mut polygon: Vec<Vector3> = [a, b, c]
+                        .iter()
+                        .map(|corner| {
+                            let corner = *corner - point;
+                            Vector3::new(corner.dot(axis_x), corner.dot(axis_y), corner.dot(axis_z))
+                        })
+                        .collect();
+
+                    for axis in 0..3 {
+                        let limit = get_axis(shape, axis);
+
+                        polygon = clip_polygon(&polygon, axis, 1.0, limit);
+                        polygon = clip_polygon(&polygon, axis, -1.0, limit);
+                    }
+
+                    if polygon.len() < 3 {
+                        continue;
+                    }
+
+                    let polygon: Vec<(Vector3, Vector2)> = polygon
+                        .iter()
+                        .map(|corner| {
+                            let world = point
+                                + axis_x * corner.x
+                                + axis_y * corner.y
+                                + axis_z * (corner.z + Self::SURFACE_OFFSET);
+                            let u = corner.x / size + 0.5;
+                            let v = 0.5 - corner.y / size;
+
+                            (
+                                world,
+                                Vector2::new(
+                                    (source.x + u * source.width) / texture.x,
+                                    (source.y + v * source.height) / texture.y,
+                                ),
+                            )
+                        })
+                        .collect();
+
+                    for i in 1..polygon.len() - 1 {
+                        vertex.push(polygon[0]);
+                        vertex.push(polygon[i]);
+                        vertex.push(polygon[i + 1]);
+                    }
+                }
+            }
+        }
+
+        Ok(Decal {
+            atlas: data.atlas.clone(),
+            frame: frame.clone(),
+            vertex,
+            color: data.color,
+            time: 0.0,
+            life: data.life,
+            fade: data.fade,
+        })
+    }
+}
+
+//================================================================
+
+/// Get a vector component by axis index.
+fn get_axis(vector: Vector3, axis: usize) -> f32 {
+    match axis {
+        0 => vector.x,
+        1 => vector.y,
+        _ => vector.z,
+    }
+}
+
+/// Clip a polygon against an axis-aligned plane, keeping the side where "sign * axis <= limit".
+fn clip_polygon(polygon: &[Vector3], axis: usize, sign: f32, limit: f32) -> Vec<Vector3> {
+    let mut result = Vec::with_capacity(polygon.len() + 1);
+
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        let distance_a = sign * get_axis(a, axis) - limit;
+        let distance_b = sign * get_axis(b, axis) - limit;
+
+        if distance_a <= 0.0 {
+            result.push(a);
+        }
+
+        if (distance_a <= 0.0) != (distance_b <= 0.0) {
+            let blend = distance_a / (distance_a - distance_b);
+            result.push(a + (b - a) * blend);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,167 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::debug::DebugCategory;
+use crate::entity::implementation::*;
+use crate::helper::Direction;
+use crate::world::*;
+
+//================================================================
+
+use engine_macro::Meta;
+use rapier3d::prelude::QueryFilter;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//================================================================
+
+#[derive(Serialize, Deserialize, Meta)]
+pub struct Decal {
+    point: Vector3,
+    angle: Vector3,
+    #[field("Name", "The decal definition name, in \"data/decal\".", "")]
+    #[serde(default)]
+    name: String,
+    #[serde(skip)]
+    info: EntityInfo,
+}
+
+impl Decal {
+    /// Maximum distance from the entity to the surface it is projected onto.
+    const RANGE: f32 = 1.0;
+
+    /// Spawn the decal onto the surface the entity is facing, or onto the entity point if none.
+    fn spawn(&self, world: &mut World) {
+        let direction = Direction::new_from_angle(&self.angle);
+        let (point, normal) = match world.scene.physical.cast_ray_surface(
+            self.point,
+            direction.x,
+            Self::RANGE,
+            None,
+            QueryFilter::default().exclude_sensors(),
+        ) {
+            Some(hit) => (hit.point, hit.normal),
+            None => (self.point, direction.x * -1.0),
+        };
+
+        world.scene.decal.spawn_fixed(&self.name, point, normal);
+    }
+}
+
+#[typetag::serde]
+impl Entity for Decal {
+    fn get_info(&self) -> &EntityInfo {
+        &self.info
+    }
+    fn get_info_mutable(&mut self) -> &mut EntityInfo {
+        &mut self.info
+    }
+
+    fn create(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        if !self.name.is_empty() {
+            self.spawn(world);
+        }
+
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        app: &mut App,
+        _context: &mut Context,
+        world: &mut World,
+        input: &str,
+    ) -> anyhow::Result<()> {
+        match input {
+            "spawn" => {
+                if !self.name.is_empty() {
+                    self.spawn(world);
+                }
+            }
+            _ => {
+                app.view
+                    .logger
+                    .print_failure(&format!("Decal::input(): Unknown input \"{input}\"."));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn tick(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        world.scene.debug.cuboid(
+            DebugCategory::Entity,
+            self.point,
+            Vector3::one() * 0.05,
+            Color::RED,
+            0.0,
+        );
+        Direction::draw_debug(
+            &mut world.scene.debug,
+            DebugCategory::Entity,
+            self.point,
+            self.angle,
+            0.0,
+        );
+
+        Ok(())
+    }
+}
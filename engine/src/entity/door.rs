@@ -49,10 +49,11 @@
 */
 
 use crate::app::*;
+use crate::debug::DebugCategory;
 use crate::entity::implementation::*;
 use crate::helper::*;
 use crate::physical::*;
-use crate::scene::View;
+use crate::scene::{InstanceOverride, View};
 use crate::world::*;
 use engine_macro::Meta;
 
@@ -69,6 +70,9 @@ use serde::{Deserialize, Serialize};
 pub struct Door {
     point: Vector3,
     angle: Vector3,
+    /// Half-extent of the view portal, along the door's right (X) and up (Y) vector.
+    #[serde(default = "Door::portal_default")]
+    portal: Vector2,
     #[serde(skip)]
     presence: Presence,
     #[serde(skip)]
@@ -77,12 +81,19 @@ pub struct Door {
     scale: f32,
     #[serde(skip)]
     view: usize,
+    /// Open (true) or close (false) state forced by a timeline input, rather than by presence.
+    #[serde(skip)]
+    force: Option<bool>,
     #[serde(skip)]
     info: EntityInfo,
 }
 
 impl Door {
     const CUBOID_SCALE: Vector3 = Vector3::new(0.6, 1.2, 0.2);
+
+    fn portal_default() -> Vector2 {
+        Vector2::new(1.2, 1.2)
+    }
 }
 
 #[typetag::serde]
@@ -128,7 +139,7 @@ impl Entity for Door {
         world.scene.set_model(context, "data/video/door_a.glb")?;
         world.scene.set_model(context, "data/video/door_b.glb")?;
 
-        self.view = View::attach(&mut world.scene, self.point, self.angle)?;
+        self.view = View::attach(&mut world.scene, self.point, self.angle, self.portal)?;
 
         Ok(())
     }
@@ -136,64 +147,35 @@ impl Entity for Door {
     fn draw_3d(
         &mut self,
         app: &mut App,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        _draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
         world: &mut World,
     ) -> anyhow::Result<()> {
         let direction = Direction::new_from_angle(&self.angle);
         let point_a = self.point + direction.z * ease_in_out_cubic(self.scale) * 1.00;
         let point_b = self.point - direction.z * ease_in_out_cubic(self.scale) * 1.35;
 
-        /*
-        let model_a = world.scene.asset.get_model("data/video/door_a.glb")?;
-
-        let color = if app.user.debug.draw_entity {
-            Color::new(255, 255, 255, 33)
+        // doors are see-through while drawing entity debug data, to show the cuboid behind.
+        let instance = if app.user.debug.has_category(DebugCategory::Entity) {
+            InstanceOverride::new_alpha(0.13)
         } else {
-            Color::WHITE
+            InstanceOverride::default()
         };
 
-        draw.draw_model_ex(
-            &model_a.model,
-            point_a,
-            direction.y,
-            self.angle.x,
-            Vector3::one(),
-            color,
-        );
-
-        let model_b = world.scene.asset.get_model("data/video/door_b.glb")?;
-
-        draw.draw_model_ex(
-            &model_b.model,
-            point_b,
-            direction.y,
-            self.angle.x,
-            Vector3::one(),
-            color,
-        );
-        */
-
         world.scene.draw_model(
             "data/video/door_a.glb",
             point_a,
             (direction.y, self.angle.x),
             Vector3::one(),
-        );
+            instance,
+        )?;
 
         world.scene.draw_model(
             "data/video/door_b.glb",
             point_b,
             (direction.y, self.angle.x),
             Vector3::one(),
-        );
-
-        if app.user.debug.draw_entity {
-            draw.draw_cube_v(
-                self.point,
-                (Self::CUBOID_SCALE + Vector3::new(0.0, 0.0, 2.0)) * 0.5,
-                Color::new(255, 0, 0, 33),
-            );
-        }
+            instance,
+        )?;
 
         Ok(())
     }
@@ -215,7 +197,22 @@ impl Entity for Door {
             )),
         );
 
-        if cast.is_some() {
+        world.scene.debug.cuboid(
+            DebugCategory::Entity,
+            self.point,
+            Self::CUBOID_SCALE + Vector3::new(0.0, 0.0, 2.0),
+            Color::RED,
+            0.0,
+        );
+        Direction::draw_debug(
+            &mut world.scene.debug,
+            DebugCategory::Entity,
+            self.point,
+            self.angle,
+            0.0,
+        );
+
+        if self.force.unwrap_or(cast.is_some()) {
             self.scale += World::TIME_STEP * 3.0;
         } else {
             self.scale -= World::TIME_STEP * 3.0;
@@ -240,4 +237,26 @@ impl Entity for Door {
 
         Ok(())
     }
+
+    fn input(
+        &mut self,
+        app: &mut App,
+        _context: &mut Context,
+        _world: &mut World,
+        input: &str,
+    ) -> anyhow::Result<()> {
+        self.force = match input {
+            "open" => Some(true),
+            "close" => Some(false),
+            "free" => None,
+            _ => {
+                app.view
+                    .logger
+                    .print_failure(&format!("Door::input(): Unknown input \"{input}\"."));
+                return Ok(());
+            }
+        };
+
+        Ok(())
+    }
 }
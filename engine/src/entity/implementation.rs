@@ -53,6 +53,7 @@ use crate::world::*;
 
 //================================================================
 
+use hashbrown::HashMap;
 use raylib::prelude::*;
 use std::any::Any;
 
@@ -152,6 +153,27 @@ pub trait Entity: Any {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn animation_event(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        _world: &mut World,
+        _data: &HashMap<String, serde_json::Value>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Named input, sent by a timeline.
+    fn input(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        _world: &mut World,
+        _input: &str,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 impl dyn Entity {
@@ -49,6 +49,7 @@
 */
 
 use crate::app::*;
+use crate::debug::DebugCategory;
 use crate::entity::implementation::*;
 use crate::scene::*;
 use crate::helper::*;
@@ -191,8 +192,9 @@ pub struct Light {
     kind: LightKind,
     #[field("Color", "The light color for this light source.", 255, 255, 255)]
     color: Color,
-    #[serde(skip)]
-    active: bool,
+    #[field("Shadow", "Cast shadows from this light source.", false)]
+    #[serde(default)]
+    shadow: bool,
     #[serde(skip)]
     power: f32,
     #[serde(skip)]
@@ -224,7 +226,6 @@ impl Entity for Light {
         context: &mut Context,
         world: &mut World,
     ) -> anyhow::Result<()> {
-        self.active = true;
         self.power = 8.00;
         self.range = 4.00;
         self.attenuation = 0.25;
@@ -239,26 +240,8 @@ impl Entity for Light {
             self.attenuation,
         )?);
 
-        Ok(())
-    }
-
-    fn draw_3d(
-        &mut self,
-        app: &mut App,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
-        world: &mut World,
-    ) -> anyhow::Result<()> {
-        if let Some(handle) = &mut self.handle {
-            let active = Room::active(&world.scene, self.point);
-
-            if (active && !self.active) || (!active && self.active) {
-                self.active = !self.active;
-                crate::scene::Light::set_enable(&mut world.scene, *handle, self.active)?;
-            }
-        }
-
-        if app.user.debug.draw_light_edit {
-            draw.draw_cube_v(self.point, Vector3::one() * 0.5, Color::RED);
+        if let Some(handle) = self.handle {
+            crate::scene::Light::set_shadow(&mut world.scene, handle, self.shadow)?;
         }
 
         Ok(())
@@ -284,13 +267,13 @@ impl Entity for Light {
                 let mut g = self.color.g as f32;
                 let mut b = self.color.b as f32;
 
-                let change_r = app.view.slider(draw, "Color (R)", &mut r, (0.0, 255.0), 1.0)?.change;
-                let change_g = app.view.slider(draw, "Color (G)", &mut g, (0.0, 255.0), 1.0)?.change;
-                let change_b = app.view.slider(draw, "Color (B)", &mut b, (0.0, 255.0), 1.0)?.change;
-                let power = app.view.slider(draw, "Power", &mut self.power, (0.0, 16.0), 0.1)?.change;
-                let range = app.view.slider(draw, "Range", &mut self.range, (0.0, 16.0), 0.1)?.change;
-                let attenuation = app.view.slider(draw, "Attenuation", &mut self.attenuation, (0.0, 0.99), 0.01)?.change;
-                app.view.switch(draw, "Kind", &mut self.kind, &[
+                let change_r = app.view.slider(draw, "light.color_r", &mut r, (0.0, 255.0), 1.0)?.change;
+                let change_g = app.view.slider(draw, "light.color_g", &mut g, (0.0, 255.0), 1.0)?.change;
+                let change_b = app.view.slider(draw, "light.color_b", &mut b, (0.0, 255.0), 1.0)?.change;
+                let power = app.view.slider(draw, "light.power", &mut self.power, (0.0, 16.0), 0.1)?.change;
+                let range = app.view.slider(draw, "light.range", &mut self.range, (0.0, 16.0), 0.1)?.change;
+                let attenuation = app.view.slider(draw, "light.attenuation", &mut self.attenuation, (0.0, 0.99), 0.01)?.change;
+                app.view.switch(draw, "light.kind", &mut self.kind, &[
                     LightKind::Normal,
                     LightKind::FlickerA,
                     LightKind::FlickerB,
@@ -378,6 +361,28 @@ impl Entity for Light {
         _context: &mut Context,
         world: &mut World,
     ) -> anyhow::Result<()> {
+        world.scene.debug.cuboid(
+            DebugCategory::Light,
+            self.point,
+            Vector3::one() * 0.25,
+            self.color,
+            0.0,
+        );
+        world.scene.debug.sphere(
+            DebugCategory::Light,
+            self.point,
+            self.range,
+            self.color,
+            0.0,
+        );
+        world.scene.debug.text(
+            DebugCategory::Light,
+            self.point,
+            &self.kind.to_string(),
+            Color::WHITE,
+            0.0,
+        );
+
         if let Some(light) = &mut self.handle
         //&& light.is_active()
         {
@@ -407,8 +412,6 @@ impl Entity for Light {
                     )?;
                 }
             }
-
-            //light.update_shadow_map();
         }
 
         Ok(())
@@ -48,10 +48,12 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+pub mod decal;
 pub mod door;
 pub mod implementation;
 pub mod light;
 pub mod particle;
 pub mod path;
 pub mod player;
+pub mod trigger;
 pub mod weapon;
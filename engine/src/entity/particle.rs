@@ -49,20 +49,28 @@
 */
 
 use crate::app::*;
+use crate::debug::DebugCategory;
 use crate::entity::implementation::*;
+use crate::particle::*;
 use crate::world::*;
 
 //================================================================
 
+use engine_macro::Meta;
 use raylib::prelude::*;
 use serde::{Deserialize, Serialize};
 
 //================================================================
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Meta)]
 pub struct Particle {
     point: Vector3,
     angle: Vector3,
+    #[field("Name", "The particle definition name, in \"data/particle\".", "")]
+    #[serde(default)]
+    name: String,
+    #[serde(skip)]
+    emitter: Option<EmitterHandle>,
     #[serde(skip)]
     info: EntityInfo,
 }
@@ -79,24 +87,84 @@ impl Entity for Particle {
     fn create(
         &mut self,
         _app: &mut App,
-        context: &mut Context,
+        _context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        if !self.name.is_empty() {
+            self.emitter = Some(world.scene.particle.attach(&self.name, self.point));
+        }
+
+        Ok(())
+    }
+
+    fn remove(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        // a burst emitter is removed on its own once its particles are dead.
+        if let Some(emitter) = self.emitter.take() {
+            let _ = world.scene.particle.detach(emitter);
+        }
+
+        Ok(())
+    }
+
+    fn input(
+        &mut self,
+        app: &mut App,
+        _context: &mut Context,
         world: &mut World,
+        input: &str,
     ) -> anyhow::Result<()> {
-        world
-            .scene
-            .asset
-            .set_texture(context, "data/video/particle.png")?;
+        match input {
+            "trigger" => {
+                // only an on-event emitter spawns on trigger, and a finished burst is gone.
+                if let Some(emitter) = self.emitter {
+                    let _ = world.scene.particle.trigger(emitter);
+                }
+            }
+            "start" => {
+                if self.emitter.is_none() && !self.name.is_empty() {
+                    self.emitter = Some(world.scene.particle.attach(&self.name, self.point));
+                }
+            }
+            "stop" => {
+                if let Some(emitter) = self.emitter.take() {
+                    let _ = world.scene.particle.detach(emitter);
+                }
+            }
+            _ => {
+                app.view
+                    .logger
+                    .print_failure(&format!("Particle::input(): Unknown input \"{input}\"."));
+            }
+        }
 
         Ok(())
     }
 
-    fn draw_3d(
+    fn tick(
         &mut self,
         _app: &mut App,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        _context: &mut Context,
         world: &mut World,
     ) -> anyhow::Result<()> {
-        draw.draw_cube_v(self.point, Vector3::one() * 0.1, Color::RED);
+        world.scene.debug.cuboid(
+            DebugCategory::Entity,
+            self.point,
+            Vector3::one() * 0.05,
+            Color::RED,
+            0.0,
+        );
+        world.scene.debug.text(
+            DebugCategory::Entity,
+            self.point,
+            &self.name,
+            Color::WHITE,
+            0.0,
+        );
 
         Ok(())
     }
@@ -54,6 +54,7 @@
 // - otherwise, move forward
 
 use crate::app::*;
+use crate::camera::*;
 use crate::entity::implementation::*;
 use crate::helper::*;
 use crate::physical::*;
@@ -63,7 +64,6 @@ use engine_macro::Meta;
 
 //================================================================
 
-use rand::Rng;
 use rapier3d::control::KinematicCharacterController;
 use rapier3d::prelude::*;
 use raylib::prelude::*;
@@ -89,9 +89,7 @@ pub struct Player {
     #[serde(skip)]
     floor: bool,
     #[serde(skip)]
-    shake: f32,
-    #[serde(skip)]
-    zoom: f32,
+    camera: CameraHandle,
     #[serde(skip)]
     push: f32,
     #[serde(skip)]
@@ -102,6 +100,27 @@ impl Player {
     const ANGLE_MIN: f32 = -90.0;
     const ANGLE_MAX: f32 = 90.00;
     const CUBOID_SCALE: Vector3 = Vector3::new(0.25, 0.50, 0.25);
+    /// Post-process damage value, per damage point.
+    const DAMAGE_POST: f32 = 0.5;
+    /// Decal name for damage, spawned on the floor below.
+    const DAMAGE_DECAL: &str = "blood";
+    /// Maximum floor distance for the damage decal.
+    const DAMAGE_DECAL_RANGE: f32 = 2.0;
+    /// Field of view kick for a push dash, at full push.
+    const PUSH_KICK: f32 = 25.0;
+    /// Shake trauma for a slam landing, at full speed.
+    const SLAM_TRAUMA: f32 = 0.8;
+
+    /// Get the player's speed.
+    pub fn get_speed(&self) -> Vector3 {
+        self.speed
+    }
+
+    /// Check if the player is in control: the player's camera is the active one, and no timeline
+    /// is locking input.
+    fn is_control(&self, world: &World) -> bool {
+        world.scene.camera.is_active(self.camera) && !world.is_input_lock()
+    }
 }
 
 #[typetag::serde]
@@ -135,6 +154,14 @@ impl Entity for Player {
             app.user.video_field,
         );
 
+        self.camera = world.scene.camera.attach(
+            CameraSource::Entity {
+                pose: CameraPose::default(),
+            },
+            CameraSystem::PRIORITY_PLAYER,
+            CameraSystem::BLEND,
+        );
+
         Ok(())
     }
 
@@ -144,57 +171,50 @@ impl Entity for Player {
         draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
         world: &mut World,
     ) -> anyhow::Result<()> {
-        if app.user.debug.draw_physical {
-            world.scene.physical.draw();
+        if world.scene.camera.is_active(self.camera)
+            && let Some(wield) = &mut self.wield
+        {
+            wield.draw_3d(app, draw, world)?;
         }
 
         if !draw.is_cursor_hidden() {
             return Ok(());
         }
 
-        app.user.input_move_x_a.poll(draw);
-        app.user.input_move_x_b.poll(draw);
-        app.user.input_move_z_a.poll(draw);
-        app.user.input_move_z_b.poll(draw);
-        app.user.input_jump.poll(draw);
-        app.user.input_push.poll(draw);
-        app.user.input_pull.poll(draw);
+        // only input is locked out: the view still follows the player.
+        if self.is_control(world) {
+            app.user.input_move_x_a.poll(draw);
+            app.user.input_move_x_b.poll(draw);
+            app.user.input_move_z_a.poll(draw);
+            app.user.input_move_z_b.poll(draw);
+            app.user.input_jump.poll(draw);
+            app.user.input_push.poll(draw);
+            app.user.input_pull.poll(draw);
 
-        //================================================================
+            //================================================================
 
-        let mouse = &draw.get_mouse_delta();
+            let mouse = &draw.get_mouse_delta();
 
-        self.angle.x -= mouse.x * 0.1 * app.user.input_mouse_scale;
-        self.angle.y += mouse.y * 0.1 * app.user.input_mouse_scale;
-        self.angle.x %= 359.0;
-        self.angle.y = self.angle.y.clamp(Self::ANGLE_MIN, Self::ANGLE_MAX);
+            self.angle.x -= mouse.x * 0.1 * app.user.input_mouse_scale;
+            self.angle.y += mouse.y * 0.1 * app.user.input_mouse_scale;
+            self.angle.x %= 359.0;
+            self.angle.y = self.angle.y.clamp(Self::ANGLE_MIN, Self::ANGLE_MAX);
+        }
 
         //================================================================
 
-        let shake = {
-            if self.shake > 0.0 {
-                let mut random = rand::rng();
-                Vector3::new(
-                    random.random_range(-self.shake..self.shake),
-                    random.random_range(-self.shake..self.shake),
-                    random.random_range(-self.shake..self.shake),
-                )
-            } else {
-                Vector3::zero()
-            }
-        };
-
-        self.view.blend(draw, &PlayerState::view(self, app, draw));
+        self.view
+            .blend(world.scene.frame_time, &PlayerState::view(self, app, draw));
 
         let direction =
             Direction::new_from_angle(&(self.angle + Vector3::new(0.0, 0.0, self.view.angle.z)));
-        let point = self.point + shake + self.view.point;
+        let point = self.point + self.view.point;
         let focus = point + Vector3::new(self.view.angle.x, self.view.angle.y, 0.0) + direction.x;
 
-        world.scene.camera_3d.position = point;
-        world.scene.camera_3d.target = focus;
-        world.scene.camera_3d.up = direction.y;
-        world.scene.camera_3d.fovy = self.view.scale;
+        world.scene.camera.set_pose(
+            self.camera,
+            CameraPose::new(point, focus, direction.y, self.view.scale),
+        );
 
         Ok(())
     }
@@ -209,31 +229,25 @@ impl Entity for Player {
             draw.draw_text(&draw.get_fps().to_string(), 8, 8, 32, Color::WHITE);
         }
 
-        let full = Vector2::new(
-            draw.get_render_width() as f32,
-            draw.get_render_height() as f32,
-        );
-        let half = full * 0.5;
-
-        draw.draw_circle_v(half, 8.0, Color::new(0, 0, 0, 127));
-        draw.draw_circle_sector(
-            half,
-            8.0,
-            -90.0,
-            interpolate(-90.0, 270.0, self.push),
-            16,
-            Color::RED,
-        );
-        draw.draw_circle_v(half, 4.0, Color::WHITE);
-
-        let time = (world.time).min(1.0);
-        let scale = ease_in_out_cubic(time);
+        if world.scene.camera.is_active(self.camera) {
+            let half = Vector2::new(
+                draw.get_render_width() as f32,
+                draw.get_render_height() as f32,
+            ) * 0.5;
+
+            draw.draw_circle_v(half, 8.0, Color::new(0, 0, 0, 127));
+            draw.draw_circle_sector(
+                half,
+                8.0,
+                -90.0,
+                interpolate(-90.0, 270.0, self.push),
+                16,
+                Color::RED,
+            );
+            draw.draw_circle_v(half, 4.0, Color::WHITE);
+        }
 
-        draw.draw_rectangle_v(
-            Vector2::zero(),
-            full,
-            Color::BLACK.lerp(Color::new(0, 0, 0, 0), scale),
-        );
+        world.fade.draw(draw);
 
         if let Some(wield) = &mut self.wield {
             wield.draw_2d(app, draw, world)?;
@@ -252,17 +266,20 @@ impl Entity for Player {
             return Ok(());
         }
 
+        // out of control, the player still falls and moves, but does not read any input.
+        let control = self.is_control(world);
+
         // TO-DO fix being able to jump off side of rigid body
         // TO-DO fix snap-to-ground on slope
-        PlayerState::tick(self, app, world, &context.handle)?;
+        PlayerState::tick(self, app, world, &context.handle, control)?;
 
         //================================================================
 
-        self.shake = (self.shake - World::TIME_STEP * self.shake * 4.0).max(0.0);
-        self.zoom = (self.zoom - World::TIME_STEP * self.zoom * 2.0).max(0.0);
-
+        // a wielded item only reads polled input, which is not polled out of control.
         if let Some(wield) = &mut self.wield {
             wield.tick(app, context, world)?;
+        } else if !control {
+            self.push = 0.0;
         } else {
             let angle = Direction::new_from_angle(&self.angle);
 
@@ -301,7 +318,7 @@ impl Entity for Player {
                     );
 
                     if cast.is_some() {
-                        self.shake = self.push * 0.15;
+                        world.scene.camera.add_trauma(self.push);
 
                         // TO-DO past a certain threshold, ignore angle on push jump and just push upward anyway?
                         let boost = angle.x * 5.0 * self.push;
@@ -322,7 +339,7 @@ impl Entity for Player {
                             self.speed.z = 0.0;
                             self.state = PlayerState::Slam { time: 0.0 };
                         } else {
-                            self.zoom = self.push;
+                            world.scene.camera.add_kick(self.push * Self::PUSH_KICK);
 
                             self.speed += angle.x * 5.0 * self.push;
                         }
@@ -345,6 +362,35 @@ impl Entity for Player {
 
         Ok(())
     }
+
+    fn damage(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        world: &mut World,
+        _other: &mut dyn Entity,
+        count: u32,
+    ) -> anyhow::Result<()> {
+        world
+            .scene
+            .post
+            .set_damage(count as f32 * Self::DAMAGE_POST);
+
+        if let Some(hit) = world.scene.physical.cast_ray_surface(
+            self.point,
+            Vector3::up() * -1.0,
+            Self::DAMAGE_DECAL_RANGE,
+            Some(self.presence.rigid),
+            QueryFilter::default().exclude_sensors(),
+        ) {
+            world
+                .scene
+                .decal
+                .spawn(Self::DAMAGE_DECAL, hit.point, hit.normal);
+        }
+
+        Ok(())
+    }
 }
 
 //================================================================
@@ -387,27 +433,34 @@ impl PlayerState {
         app: &App,
         world: &mut World,
         handle: &RaylibHandle,
+        control: bool,
     ) -> anyhow::Result<()> {
         match player.state {
             Self::Walk { ref mut jump } => {
                 *jump -= *jump * World::TIME_STEP * 4.0;
 
-                let move_angle = Direction::new_from_angle(&Vector3::new(player.angle.x, 0.0, 0.0));
-                let move_x = move_angle.x
-                    * Self::get_movement_key(
-                        handle,
-                        app.user.input_move_x_a,
-                        app.user.input_move_x_b,
-                    );
-                let move_z = move_angle.z
-                    * Self::get_movement_key(
-                        handle,
-                        app.user.input_move_z_a,
-                        app.user.input_move_z_b,
-                    );
-                let move_which = move_x + move_z;
+                let move_which = if control {
+                    let move_angle =
+                        Direction::new_from_angle(&Vector3::new(player.angle.x, 0.0, 0.0));
+                    let move_x = move_angle.x
+                        * Self::get_movement_key(
+                            handle,
+                            app.user.input_move_x_a,
+                            app.user.input_move_x_b,
+                        );
+                    let move_z = move_angle.z
+                        * Self::get_movement_key(
+                            handle,
+                            app.user.input_move_z_a,
+                            app.user.input_move_z_b,
+                        );
+
+                    move_x + move_z
+                } else {
+                    Vector3::zero()
+                };
 
-                if player.floor {
+                if control && player.floor {
                     if app.user.input_jump.get_down(handle) {
                         player.speed.y = 2.75;
                         player.floor = false;
@@ -432,8 +485,8 @@ impl PlayerState {
                 player.speed.y -= 8.0_f32.powf(*time + 1.0) * World::TIME_STEP;
 
                 if player.floor {
-                    let shake = (player.speed.y.abs() / 64.0).min(1.0) * 0.1;
-                    player.shake = shake;
+                    let trauma = (player.speed.y.abs() / 64.0).min(1.0) * Player::SLAM_TRAUMA;
+                    world.scene.camera.add_trauma(trauma);
                     player.state = Self::Walk { jump: 0.0 }
                 }
 
@@ -473,7 +526,7 @@ impl PlayerState {
                             0.0,
                         ),
                     Vector3::new(0.0, jump * 0.1, tilt),
-                    app.user.video_field + player.zoom * 25.0,
+                    app.user.video_field,
                 )
             }
             Self::Slam { .. } => Target::new(
@@ -497,6 +550,16 @@ pub trait Wield {
         Ok(())
     }
 
+    /// Draw the wielded item in hand. Only called while the player's camera is active.
+    fn draw_3d(
+        &mut self,
+        _app: &mut App,
+        _draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        _world: &mut World,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     fn draw_2d(
         &mut self,
         _app: &mut App,
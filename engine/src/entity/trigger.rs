@@ -0,0 +1,181 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+use crate::app::*;
+use crate::debug::DebugCategory;
+use crate::entity::implementation::*;
+use crate::physical::*;
+use crate::timeline::*;
+use crate::world::*;
+use engine_macro::Meta;
+
+//================================================================
+
+use rapier3d::prelude::*;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//================================================================
+
+#[derive(Serialize, Deserialize, Meta)]
+#[info("Timeline trigger volume.", 1.0, 1.0, 1.0)]
+pub struct Trigger {
+    point: Vector3,
+    angle: Vector3,
+    /// Half-extent of the trigger volume.
+    #[serde(default = "Trigger::scale_default")]
+    scale: Vector3,
+    #[field("Timeline", "The timeline name, in \"data/timeline\".", "")]
+    #[serde(default)]
+    timeline: String,
+    #[field("Once", "Only play on the first enter.", true)]
+    #[serde(default = "Trigger::once_default")]
+    once: bool,
+    /// The player was inside the volume on the last tick.
+    #[serde(skip)]
+    inside: bool,
+    /// The timeline has been played at least once.
+    #[serde(skip)]
+    fire: bool,
+    #[serde(skip)]
+    info: EntityInfo,
+}
+
+impl Trigger {
+    fn scale_default() -> Vector3 {
+        Vector3::one() * 0.5
+    }
+
+    fn once_default() -> bool {
+        true
+    }
+}
+
+#[typetag::serde]
+impl Entity for Trigger {
+    fn get_info(&self) -> &EntityInfo {
+        &self.info
+    }
+    fn get_info_mutable(&mut self) -> &mut EntityInfo {
+        &mut self.info
+    }
+
+    fn create(
+        &mut self,
+        _app: &mut App,
+        _context: &mut Context,
+        _world: &mut World,
+    ) -> anyhow::Result<()> {
+        // load the timeline once on level load, so that a broken timeline fails early.
+        if !self.timeline.is_empty() {
+            TimelineData::new(&self.timeline)?;
+        }
+
+        Ok(())
+    }
+
+    fn tick(
+        &mut self,
+        _app: &mut App,
+        context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        world.scene.debug.cuboid(
+            DebugCategory::Entity,
+            self.point,
+            self.scale,
+            Color::GREEN,
+            0.0,
+        );
+        world.scene.debug.text(
+            DebugCategory::Entity,
+            self.point,
+            &self.timeline,
+            Color::WHITE,
+            0.0,
+        );
+
+        if self.timeline.is_empty() || (self.once && self.fire) {
+            return Ok(());
+        }
+
+        let cast = world.scene.physical.intersect_cuboid_list(
+            self.point,
+            self.angle,
+            self.scale,
+            None,
+            QueryFilter::default().groups(InteractionGroups::new(
+                Physical::GROUP_ENTITY,
+                Physical::GROUP_ENTITY,
+            )),
+        );
+
+        // the player may share the trigger with any number of other entities.
+        let mut inside = false;
+
+        for collider in cast {
+            if let Some(entity) = world.entity_from_collider(collider)?
+                && Some(entity.get_info().index) == world.player
+            {
+                inside = true;
+                break;
+            }
+        }
+
+        // only play on enter, rather than on every tick the player is inside.
+        if inside && !self.inside {
+            self.fire = true;
+            world.timeline_play(context, &self.timeline)?;
+        }
+
+        self.inside = inside;
+
+        Ok(())
+    }
+}
@@ -48,12 +48,14 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::animation::Animator;
 use crate::app::*;
+use crate::debug::DebugCategory;
 use crate::entity::implementation::*;
 use crate::entity::player::*;
+use crate::helper::{Animation, Attachment, Direction};
 use crate::physical::*;
-use crate::helper::Direction;
-use crate::helper::draw_model_transform;
+use crate::scene::InstanceOverride;
 use crate::world::*;
 
 //================================================================
@@ -76,14 +78,159 @@ pub struct Weapon {
     force: Option<Vector3>,
     #[serde(skip)]
     presence: Presence,
+    /// Clip play-back state. None if the model has no idle clip, or has an animation graph.
+    #[serde(skip)]
+    animation: Option<Animation>,
+    /// Animation graph play-back state. None if the model has no animation graph.
+    #[serde(skip)]
+    animator: Option<Animator>,
     #[serde(skip)]
     info: EntityInfo,
 }
 
 impl Weapon {
+    /// Decal name for a hit on level geometry.
+    const DECAL_HIT: &str = "bullet";
+    const MODEL: &str = "data/video/weapon.glb";
+    /// Clip played while idle, on repeat.
+    const CLIP_IDLE: &str = "idle";
+    /// Clip played once on each shot, if the model has one.
+    const CLIP_FIRE: &str = "fire";
+    /// Clip play-back rate, in frames per second.
+    const CLIP_RATE: f32 = 30.0;
+    /// Animation graph parameter for the owner's horizontal speed.
+    const PARAMETER_SPEED: &str = "speed";
+    /// Animation graph layer played once on each shot, if the graph has one.
+    const LAYER_RECOIL: &str = "recoil";
+    /// Weapon point in hand, relative to the camera.
+    const HAND_POINT: Vector3 = Vector3::new(0.25, -0.25, -0.5);
+    /// Bone for the muzzle, where the muzzle flash and animation events play from while in hand.
+    /// The weapon's origin is used if the model has no such bone.
+    const MUZZLE_BONE: &str = "muzzle";
+    /// Particle name for the muzzle flash.
+    const MUZZLE_PARTICLE: &str = "muzzle";
+
     fn ammo_default() -> u32 {
         8
     }
+
+    /// Start the animation graph, if the model has one. Otherwise, start the idle clip, if the
+    /// model has one.
+    fn animation_start(&mut self, world: &mut World) -> anyhow::Result<()> {
+        let model = world.scene.asset.get_model(Self::MODEL)?;
+
+        if model.graph.is_some() {
+            self.animator = Some(Animator::new(model)?);
+            self.animation = None;
+        } else {
+            self.animator = None;
+            self.animation = Animation::new(model, Self::CLIP_IDLE, Self::CLIP_RATE, true).ok();
+        }
+
+        Ok(())
+    }
+
+    /// Play the shot animation: the recoil layer for a graph, or the fire clip otherwise.
+    fn animation_fire(&mut self, world: &mut World) -> anyhow::Result<()> {
+        let model = world.scene.asset.get_model(Self::MODEL)?;
+
+        if let Some(animator) = &mut self.animator {
+            if model
+                .graph
+                .as_ref()
+                .is_some_and(|graph| graph.layer.iter().any(|l| l.name == Self::LAYER_RECOIL))
+            {
+                animator.play_layer(Self::LAYER_RECOIL, 1.0);
+            }
+        } else if let Some(animation) = &mut self.animation
+            && model.get_animation(Self::CLIP_FIRE).is_ok()
+        {
+            animation.set_clip(Self::CLIP_FIRE, false);
+        }
+
+        Ok(())
+    }
+
+    /// Advance the animation graph or the active clip, going back to the idle clip once a
+    /// non-repeating clip is over.
+    fn animation_update(
+        &mut self,
+        app: &App,
+        world: &mut World,
+        point: Vector3,
+        speed: f32,
+        owner: usize,
+    ) -> anyhow::Result<()> {
+        if let Some(animator) = &mut self.animator {
+            animator.set_parameter(Self::PARAMETER_SPEED, speed);
+            animator.update(app, world, Self::MODEL, point, owner)?;
+        } else if let Some(animation) = &mut self.animation {
+            animation.update(app, world, Self::MODEL, point, owner)?;
+
+            if animation.is_finish(world.scene.asset.get_model(Self::MODEL)?)? {
+                animation.set_clip(Self::CLIP_IDLE, true);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the weapon's transform in hand, from the active camera.
+    fn hand_transform(world: &World) -> Matrix {
+        let camera = world.scene.camera_3d;
+        let view = Matrix::look_at(camera.position, camera.target, camera.up).inverted();
+        let hand = Matrix::translate(Self::HAND_POINT.x, Self::HAND_POINT.y, Self::HAND_POINT.z);
+
+        hand * view
+    }
+
+    /// Get the world point of the muzzle, for a given weapon transform.
+    fn muzzle_point(&self, world: &mut World, transform: Matrix) -> anyhow::Result<Vector3> {
+        let model = world.scene.asset.get_model(Self::MODEL)?;
+        let muzzle = Attachment::new(Self::MUZZLE_BONE, Vector3::zero(), Vector3::zero());
+
+        if model.get_bone(Self::MUZZLE_BONE).is_some() {
+            if let Some(animator) = &self.animator {
+                return Ok(Attachment::get_point(
+                    muzzle.from_animator(model, animator, transform)?,
+                ));
+            }
+
+            if let Some(animation) = &self.animation {
+                return Ok(Attachment::get_point(
+                    muzzle.from_animation(model, animation, transform)?,
+                ));
+            }
+        }
+
+        Ok(Attachment::get_point(transform))
+    }
+
+    /// Submit the model for drawing, in its current pose if animated.
+    fn draw_model(&self, world: &mut World, transform: Matrix) -> anyhow::Result<()> {
+        let model = world.scene.asset.get_model(Self::MODEL)?;
+        let pose = if let Some(animator) = &self.animator {
+            Some(animator.get_pose(model)?)
+        } else if let Some(animation) = &self.animation {
+            Some(animation.get_pose(model)?)
+        } else {
+            None
+        };
+
+        match pose {
+            Some(pose) => world.scene.draw_model_pose(
+                Self::MODEL,
+                transform,
+                InstanceOverride::default(),
+                pose,
+            ),
+            None => {
+                world
+                    .scene
+                    .draw_model_matrix(Self::MODEL, transform, InstanceOverride::default())
+            }
+        }
+    }
 }
 
 #[typetag::serde]
@@ -101,10 +248,8 @@ impl Entity for Weapon {
         context: &mut Context,
         world: &mut World,
     ) -> anyhow::Result<()> {
-        world
-            .scene
-            .asset
-            .set_model(context, "data/video/weapon.glb")?;
+        world.scene.set_model(context, Self::MODEL)?;
+        self.animation_start(world)?;
 
         self.presence = Presence::new_rigid_cuboid_dynamic(
             &mut world.scene.physical,
@@ -146,30 +291,33 @@ impl Entity for Weapon {
     fn draw_3d(
         &mut self,
         _app: &mut App,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        _draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
         world: &mut World,
     ) -> anyhow::Result<()> {
-        let model = world.scene.asset.get_model("data/video/weapon.glb")?;
-
         let transform = world
             .scene
             .physical
             .get_rigid_transform(self.presence.rigid)?;
 
-        draw_model_transform(draw, model, transform);
-
-        Ok(())
+        self.draw_model(world, transform)
     }
 
     fn tick(
         &mut self,
-        _app: &mut App,
+        app: &mut App,
         _context: &mut Context,
-        _world: &mut World,
+        world: &mut World,
     ) -> anyhow::Result<()> {
         self.grab = (self.grab - World::TIME_STEP).max(0.0);
 
-        Ok(())
+        let point = Vector3::zero().transform_with(
+            world
+                .scene
+                .physical
+                .get_rigid_transform(self.presence.rigid)?,
+        );
+
+        self.animation_update(app, world, point, 0.0, self.info.index)
     }
 
     fn interact(
@@ -189,6 +337,15 @@ impl Entity for Weapon {
 }
 
 impl Wield for Weapon {
+    fn draw_3d(
+        &mut self,
+        _app: &mut App,
+        _draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        self.draw_model(world, Self::hand_transform(world))
+    }
+
     fn draw_2d(
         &mut self,
         _app: &mut App,
@@ -225,27 +382,59 @@ impl Wield for Weapon {
             let angle = Direction::new_from_angle(&player.angle);
 
             unsafe {
-                let cast = (*wrl).scene.physical.cast_ray(
+                let cast = (*wrl).scene.physical.cast_ray_surface(
                     (*wrl).scene.camera_3d.position,
                     angle.x,
                     16.0,
-                    true,
                     Some(player.presence.rigid),
                     QueryFilter::default().exclude_sensors(),
                 );
 
-                if let Some((collider, _)) = cast
-                    && let Ok(Some(entity)) = (*wrl).entity_from_collider_mutable(collider)
-                {
-                    entity.damage(app, context, &mut *wrl, player, 1)?;
+                (*wrl).scene.debug.ray(
+                    DebugCategory::Weapon,
+                    (*wrl).scene.camera_3d.position,
+                    angle.x * 16.0,
+                    cast.map(|hit| hit.point),
+                    Color::YELLOW,
+                    1.0,
+                );
+
+                if let Some(hit) = cast {
+                    if let Ok(Some(entity)) = (*wrl).entity_from_collider_mutable(hit.collider) {
+                        entity.damage(app, context, &mut *wrl, player, 1)?;
+                    } else {
+                        (*wrl)
+                            .scene
+                            .decal
+                            .spawn(Self::DECAL_HIT, hit.point, hit.normal);
+                    }
                 }
             }
 
             self.ammo -= 1;
+
+            let muzzle = self.muzzle_point(world, Self::hand_transform(world))?;
+            world.scene.particle.spawn(Self::MUZZLE_PARTICLE, muzzle);
+
+            self.animation_fire(world)?;
         }
 
         let point = world.scene.camera_3d.position;
 
+        if let Some(owner) = world.player {
+            let muzzle = self.muzzle_point(world, Self::hand_transform(world))?;
+            let speed = world
+                .entity_find_type::<Player>(owner)
+                .map(|player| {
+                    let speed = player.get_speed();
+
+                    Vector2::new(speed.x, speed.z).length()
+                })
+                .unwrap_or_default();
+
+            self.animation_update(app, world, muzzle, speed, owner)?;
+        }
+
         if app.user.input_pull.get_press()
             && let Some(player) = world.player
             && let Some(player) = world.entity_find_mutable_type::<Player>(player)
@@ -259,6 +448,8 @@ impl Wield for Weapon {
                 grab: 0.25,
                 force: Some(angle.x * 2.0),
                 presence: Presence::default(),
+                animation: None,
+                animator: None,
                 info: EntityInfo::default(),
             };
 
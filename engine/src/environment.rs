@@ -0,0 +1,350 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::asset::*;
+use crate::shader::*;
+
+//================================================================
+
+use hashbrown::HashMap;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+
+//================================================================
+
+/// Fog mode.
+#[derive(Debug, Serialize, Deserialize, Default, Copy, Clone, PartialEq)]
+pub enum FogMode {
+    #[default]
+    Off,
+    /// Fog grows linearly from "fog_start" to "fog_end".
+    Linear,
+    /// Fog grows exponentially by "fog_density".
+    Exponential,
+}
+
+/// Environment state, which can be blended from one room to another.
+#[derive(Debug, Deserialize, Copy, Clone)]
+#[serde(default)]
+pub struct EnvironmentState {
+    /// Ambient light color.
+    pub ambient: Vector4,
+    /// Fog mode.
+    pub fog_mode: FogMode,
+    /// Fog color.
+    pub fog_color: Vector4,
+    /// Fog density, for exponential fog.
+    pub fog_density: f32,
+    /// Fog start distance, for linear fog.
+    pub fog_start: f32,
+    /// Fog end distance, for linear fog.
+    pub fog_end: f32,
+}
+
+impl Default for EnvironmentState {
+    fn default() -> Self {
+        Self {
+            ambient: Vector4::new(0.5, 0.5, 0.5, 1.0),
+            fog_mode: FogMode::Off,
+            fog_color: Vector4::new(0.5, 0.5, 0.5, 1.0),
+            fog_density: 0.05,
+            fog_start: 8.0,
+            fog_end: 32.0,
+        }
+    }
+}
+
+/// Per-room environment override. Every field left out will use the level value.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EnvironmentOverride {
+    pub ambient: Option<Vector4>,
+    pub fog_mode: Option<FogMode>,
+    pub fog_color: Option<Vector4>,
+    pub fog_density: Option<f32>,
+    pub fog_start: Option<f32>,
+    pub fog_end: Option<f32>,
+}
+
+impl EnvironmentOverride {
+    /// Apply this override on top of a given state.
+    fn apply(&self, state: EnvironmentState) -> EnvironmentState {
+        EnvironmentState {
+            ambient: self.ambient.unwrap_or(state.ambient),
+            fog_mode: self.fog_mode.unwrap_or(state.fog_mode),
+            fog_color: self.fog_color.unwrap_or(state.fog_color),
+            fog_density: self.fog_density.unwrap_or(state.fog_density),
+            fog_start: self.fog_start.unwrap_or(state.fog_start),
+            fog_end: self.fog_end.unwrap_or(state.fog_end),
+        }
+    }
+}
+
+/// Level environment block, from the level file.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct EnvironmentData {
+    /// Level environment state.
+    #[serde(flatten)]
+    pub state: EnvironmentState,
+    /// Path to a sky cube-map image, if any.
+    pub sky: Option<String>,
+    /// Per-room override, keyed by the room's model file name in the level's room list.
+    pub room: HashMap<String, EnvironmentOverride>,
+}
+
+/// Scene environment. Blends toward the state of the room the camera is in.
+#[derive(Default)]
+pub struct Environment {
+    /// Level environment state.
+    base: EnvironmentState,
+    /// Environment state for each room with an override, by room index.
+    room: Vec<Option<EnvironmentState>>,
+    /// Current, blended environment state.
+    state: EnvironmentState,
+    /// Current fog amount, from 0.0 to 1.0. Lets fog fade in and out when the fog mode changes.
+    fog: f32,
+    /// Sky box, if any.
+    sky: Option<Sky>,
+}
+
+impl Environment {
+    /// Blend rate toward the target state, per second.
+    const BLEND_RATE: f32 = 4.0;
+    /// Fog amount under which a pending fog mode change is applied.
+    const FOG_SWITCH: f32 = 0.01;
+
+    /// Set the level environment. The room name list must be in the same order as the room list
+    /// in the scene.
+    pub fn set_level(
+        &mut self,
+        asset: &mut Asset,
+        context: &mut Context,
+        data: EnvironmentData,
+        room_list: &[String],
+    ) -> anyhow::Result<()> {
+        for name in data.room.keys() {
+            if !room_list.contains(name) {
+                return Err(anyhow::Error::msg(format!(
+                    "Environment::set_level(): Room override \"{name}\" does not match any room."
+                )));
+            }
+        }
+
+        self.room = room_list
+            .iter()
+            .map(|name| data.room.get(name).map(|room| room.apply(data.state)))
+            .collect();
+        self.base = data.state;
+        self.state = data.state;
+        self.fog = Self::fog_target(&data.state);
+        self.sky = match data.sky {
+            Some(path) => Some(Sky::new(asset, context, &path)?),
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    /// Blend the current state toward the state of a given room, or the level state if none.
+    pub fn update(&mut self, room: Option<usize>, time: f32) {
+        let target = room
+            .and_then(|room| self.room.get(room).copied().flatten())
+            .unwrap_or(self.base);
+        let blend = 1.0 - (-Self::BLEND_RATE * time).exp();
+        let lerp = |a: f32, b: f32| a + (b - a) * blend;
+
+        // the fog mode can not be blended. fade the current fog out first, switch the mode once it
+        // is gone, then fade the new fog back in.
+        if self.state.fog_mode != target.fog_mode
+            && (self.state.fog_mode == FogMode::Off || self.fog < Self::FOG_SWITCH)
+        {
+            self.state.fog_mode = target.fog_mode;
+        }
+
+        let fog = if self.state.fog_mode == target.fog_mode {
+            Self::fog_target(&target)
+        } else {
+            0.0
+        };
+
+        self.state.ambient = self.state.ambient + (target.ambient - self.state.ambient) * blend;
+        self.state.fog_color =
+            self.state.fog_color + (target.fog_color - self.state.fog_color) * blend;
+        self.state.fog_density = lerp(self.state.fog_density, target.fog_density);
+        self.state.fog_start = lerp(self.state.fog_start, target.fog_start);
+        self.state.fog_end = lerp(self.state.fog_end, target.fog_end);
+        self.fog = lerp(self.fog, fog);
+    }
+
+    /// Upload the current state to the light shader.
+    pub fn upload(&self, shader: &mut Shader, light: &ShaderLight) {
+        shader.set_shader_value(light.ambient, self.state.ambient);
+        shader.set_shader_value(light.fog_mode, self.state.fog_mode as i32);
+        shader.set_shader_value(light.fog_color, self.state.fog_color);
+        shader.set_shader_value(light.fog_amount, self.fog);
+        shader.set_shader_value(light.fog_density, self.state.fog_density);
+        shader.set_shader_value(
+            light.fog_range,
+            Vector2::new(self.state.fog_start, self.state.fog_end),
+        );
+    }
+
+    /// Get the background clear color, which is the fog color scaled by the fog amount.
+    pub fn get_clear_color(&self) -> Color {
+        let color = self.state.fog_color * self.fog;
+
+        Color::new(
+            (color.x.clamp(0.0, 1.0) * 255.0) as u8,
+            (color.y.clamp(0.0, 1.0) * 255.0) as u8,
+            (color.z.clamp(0.0, 1.0) * 255.0) as u8,
+            255,
+        )
+    }
+
+    /// Draw the sky box, if any, around a given point.
+    pub fn draw_sky(&self, draw: &mut impl RaylibDraw3D, point: Vector3) {
+        if let Some(sky) = &self.sky {
+            sky.draw(draw, point);
+        }
+    }
+
+    fn fog_target(state: &EnvironmentState) -> f32 {
+        if state.fog_mode == FogMode::Off {
+            0.0
+        } else {
+            1.0
+        }
+    }
+}
+
+//================================================================
+
+/// Sky box, drawn as a cube around the camera with a cube-map texture.
+struct Sky {
+    /// Cube model. Owns the cube-map texture.
+    model: ffi::Model,
+}
+
+impl Sky {
+    /// Asset name of the sky shader.
+    const SHADER_NAME: &str = "sky";
+
+    fn new(asset: &mut Asset, context: &mut Context, path: &str) -> anyhow::Result<Self> {
+        let shader = asset.set_shader(
+            context,
+            Self::SHADER_NAME,
+            Some("data/shader/sky.vs"),
+            Some("data/shader/sky.fs"),
+        )?;
+
+        shader.set_shader_value(
+            shader.get_shader_location("environment_map"),
+            MaterialMapIndex::MATERIAL_MAP_CUBEMAP as i32,
+        );
+
+        let file = CString::new(path)?;
+
+        unsafe {
+            let image = ffi::LoadImage(file.as_ptr());
+
+            if image.data.is_null() {
+                return Err(anyhow::Error::msg(format!(
+                    "Sky::new(): Could not load sky image \"{path}\"."
+                )));
+            }
+
+            let texture =
+                ffi::LoadTextureCubemap(image, CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT as i32);
+
+            ffi::UnloadImage(image);
+
+            if texture.id == 0 {
+                return Err(anyhow::Error::msg(format!(
+                    "Sky::new(): Could not create sky cube-map \"{path}\"."
+                )));
+            }
+
+            let model = ffi::LoadModelFromMesh(ffi::GenMeshCube(1.0, 1.0, 1.0));
+            let material = &mut *model.materials;
+
+            material.shader = **shader;
+            (*material
+                .maps
+                .add(MaterialMapIndex::MATERIAL_MAP_CUBEMAP as usize))
+            .texture = texture;
+
+            Ok(Self { model })
+        }
+    }
+
+    fn draw(&self, _: &mut impl RaylibDraw3D, point: Vector3) {
+        unsafe {
+            ffi::rlDisableBackfaceCulling();
+            ffi::rlDisableDepthMask();
+            ffi::DrawModel(self.model, point.into(), 1.0, Color::WHITE.into());
+            ffi::rlEnableDepthMask();
+            ffi::rlEnableBackfaceCulling();
+        }
+    }
+}
+
+impl Drop for Sky {
+    fn drop(&mut self) {
+        unsafe {
+            // the shader is owned by the asset manager, so reset it to the default shader before
+            // unloading the model and the cube-map with it.
+            (*self.model.materials).shader.id = ffi::rlGetShaderIdDefault();
+            ffi::UnloadModel(self.model);
+        }
+    }
+}
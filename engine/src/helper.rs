@@ -48,9 +48,12 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::animation::*;
 use crate::app::*;
 use crate::asset::*;
+use crate::debug::*;
 use crate::physical::*;
+use crate::scene::*;
 use crate::world::*;
 
 //================================================================
@@ -58,162 +61,225 @@ use crate::world::*;
 use rapier3d::control::KinematicCharacterController;
 use rapier3d::prelude::ColliderHandle;
 use raylib::prelude::*;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
 //================================================================
 
-#[derive(Serialize, Deserialize, Default)]
+/// Per-instance model animation play-back state.
+#[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Animation {
+    /// Name of the active clip.
     pub name: String,
+    /// Play-back rate, in frames per second.
     pub rate: f32,
+    /// Current frame.
     pub frame: f32,
+    /// Restart the clip once the last frame has been reached.
+    pub repeat: bool,
 }
 
 impl Animation {
-    pub fn new(model: &mut AssetModel, name: &str, rate: f32) -> Self {
-        /* TO-DO fix
-        model
-            .model
-            .set_model_animation(model.animation.get_animation(name));
-        model.model.set_animation_frame(0);
-        */
+    /// Create a new animation state, for a given clip.
+    pub fn new(model: &AssetModel, name: &str, rate: f32, repeat: bool) -> anyhow::Result<Self> {
+        model.get_animation(name)?;
 
-        Self {
+        Ok(Self {
             name: name.to_string(),
             rate,
             frame: 0.0,
+            repeat,
+        })
+    }
+
+    /// Switch to a different clip. The frame is only reset if the clip is not already active.
+    pub fn set_clip(&mut self, name: &str, repeat: bool) {
+        if self.name != name {
+            self.name = name.to_string();
+            self.frame = 0.0;
         }
+
+        self.repeat = repeat;
     }
 
-    /*
-    pub fn update<
-        F: FnMut(&mut World, &HashMap<String, serde_json::Value>) -> anyhow::Result<()>,
-    >(
+    /// Check if a non-repeating clip has reached its last frame.
+    pub fn is_finish(&self, model: &AssetModel) -> anyhow::Result<bool> {
+        let count = model.get_animation(&self.name)?.frameCount;
+
+        Ok(!self.repeat && self.frame as i32 >= count - 1)
+    }
+
+    /// Advance the animation by one game tick. Every event on a frame crossed during the tick will
+    /// fire: sound events play at the given point, custom events are queued for the owner entity.
+    pub fn update(
         &mut self,
+        app: &App,
         world: &mut World,
         path: &str,
-        call: Option<F>,
+        point: Vector3,
+        owner: usize,
     ) -> anyhow::Result<()> {
-        let wrl = world as *mut World;
         let model = world.scene.asset.get_model(path)?;
+        let count = model.get_animation(&self.name)?.frameCount;
 
-        if let Some(animation) = model.model.get_model_animation() {
-            let delta = self.frame + World::TIME_STEP * self.rate;
-
-            if (self.frame as i32) < (delta as i32) {
-                let delta = delta as i32 - 1;
-                let frame = model.event.map.get(&self.name);
-                model.model.set_animation_frame(delta);
-
-                if let Some(frame) = frame
-                    && let Some(event) = frame.get(&delta)
-                {
-                    unsafe {
-                        match event {
-                            crate::asset::AnimationEvent::Print { data } => println!("{data}"),
-                            crate::asset::AnimationEvent::Sound { path } => {
-                                (*wrl).scene.asset.get_sound(path)?.sound.play();
-                            }
-                            crate::asset::AnimationEvent::Custom(data) => {
-                                if let Some(mut call) = call {
-                                    call(&mut (*wrl), data)?
-                                }
-                            }
-                        }
-                    }
+        if count <= 0 {
+            return Ok(());
+        }
+
+        let delta = self.frame + World::TIME_STEP * self.rate;
+        let event_list =
+            Self::event_cross(model, &self.name, self.frame, delta, count, self.repeat);
+
+        self.frame = if self.repeat {
+            delta % count as f32
+        } else {
+            delta.min((count - 1) as f32)
+        };
+
+        Self::event_fire(app, world, event_list, point, owner)
+    }
+
+    /// Get every event on a frame crossed while advancing a clip from one frame to another.
+    pub(crate) fn event_cross(
+        model: &AssetModel,
+        name: &str,
+        from: f32,
+        to: f32,
+        count: i32,
+        repeat: bool,
+    ) -> Vec<AnimationEvent> {
+        let mut event_list = Vec::new();
+
+        if let Some(event) = model.event.map.get(name) {
+            for frame in (from.ceil() as i32)..(to.ceil() as i32) {
+                if !repeat && frame >= count {
+                    break;
                 }
-            }
 
-            self.frame += World::TIME_STEP * self.rate;
-            self.frame %= animation.get_frame_count() as f32 + 1.0;
+                if let Some(event) = event.get(&(frame % count)) {
+                    event_list.push(event.clone());
+                }
+            }
         }
 
-        Ok(())
+        event_list
     }
-    */
 
-    pub fn update(
-        &mut self,
-        app: &mut App,
+    /// Fire a list of animation events. Sound events play at the given point, custom events are
+    /// queued for the owner entity.
+    pub(crate) fn event_fire(
+        app: &App,
         world: &mut World,
-        path: &str,
+        event_list: Vec<AnimationEvent>,
         point: Vector3,
+        owner: usize,
     ) -> anyhow::Result<()> {
-        let wrl = world as *mut World;
-        let model = world.scene.asset.get_model(path)?;
-
-        // TO-DO fix
-        /*
-        // TO-DO probably not a good idea to be getting the model animation each frame...fix.
-        if let Some(animation) = model.model.get_model_animation() {
-            let delta = self.frame + World::TIME_STEP * self.rate;
-
-            if (self.frame as i32) < (delta as i32) {
-                let delta = delta as i32 - 1;
-                let frame = model.event.map.get(&self.name);
-                model.model.set_animation_frame(delta);
-
-                if let Some(frame) = frame
-                    && let Some(event) = frame.get(&delta)
-                {
-                    unsafe {
-                        match event {
-                            crate::asset::AnimationEvent::Sound { path } => {
-                                (*wrl).scene.sound_play(app, path, Some(point))?
-                            }
-                            crate::asset::AnimationEvent::Custom(_) => {}
-                        }
-                    }
+        for event in event_list {
+            match event {
+                AnimationEvent::Sound { path } => {
+                    Noise::sound_play(&mut world.scene, app, &path, Some(point))?
                 }
+                AnimationEvent::Particle { name } => world.scene.particle.spawn(&name, point),
+                AnimationEvent::Custom(data) => world.animation_event.push((owner, data)),
             }
-
-            self.frame += World::TIME_STEP * self.rate;
-            self.frame %= animation.get_frame_count() as f32;
         }
-        */
 
         Ok(())
     }
 
+    /// Get the pose at the current frame, to draw with "Scene::draw_model_pose".
+    pub fn get_pose(&self, model: &AssetModel) -> anyhow::Result<Pose> {
+        Ok(Pose::sample(model.get_animation(&self.name)?, self.frame))
+    }
+
+    /// Get the model-space point, angle and scale of a bone at the current frame. None if the
+    /// model has no such bone.
     pub fn get_bone_data(
         &self,
         model: &AssetModel,
         bone_name: &str,
     ) -> anyhow::Result<Option<(Vector3, Vector4, Vector3)>> {
-        /*
-        let frame = model
-            .animation
-            .get_animation(&self.name)
-            .ok_or(anyhow::Error::msg(
-                "Animation::get_bone_data(): Could not find animation.",
-            ))?;
-
-        let mut bone_index = None;
-
-        // find the actual bone index into the pose array.
-        for (i, bone) in frame.get_bone_info().iter().enumerate() {
-            let name = String::from_utf8(bone.name.iter().map(|&c| c as u8).collect()).unwrap();
-            let name = name.trim_matches(char::from(0));
-            if name == bone_name {
-                bone_index = Some(i);
-                break;
-            }
+        let animation = model.get_animation(&self.name)?;
+
+        if let Some(bone) = model.get_bone(bone_name) {
+            return Ok(Pose::sample(animation, self.frame).get_bone(bone));
         }
 
-        // found bone...
-        if let Some(bone_index) = bone_index {
-            // get bone data, and return it.
-            let frame = frame.get_frame_global_poses(self.frame as usize)[bone_index];
+        Ok(None)
+    }
+}
 
-            let (point, angle, scale) = matrix_decompose(&frame);
+/// An attachment point on a model's bone, such as a hand or a weapon's tip.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Attachment {
+    /// Bone name.
+    pub bone: String,
+    /// Point offset, relative to the bone.
+    pub point: Vector3,
+    /// Angle offset (in degrees), relative to the bone.
+    pub angle: Vector3,
+}
 
-            return Ok(Some((point, angle, scale)));
+impl Attachment {
+    /// Create a new attachment point.
+    pub fn new(bone: &str, point: Vector3, angle: Vector3) -> Self {
+        Self {
+            bone: bone.to_string(),
+            point,
+            angle,
         }
+    }
 
-        Ok(None)
-        */
+    /// Get the world transform of the attachment, from a parent model's clip play-back state and
+    /// the parent model's world transform.
+    pub fn from_animation(
+        &self,
+        model: &AssetModel,
+        animation: &Animation,
+        parent: Matrix,
+    ) -> anyhow::Result<Matrix> {
+        let bone = animation.get_bone_data(model, &self.bone)?;
 
-        todo!()
+        self.get_transform(bone, parent)
+    }
+
+    /// Get the world transform of the attachment, from a parent model's animator and the parent
+    /// model's world transform.
+    pub fn from_animator(
+        &self,
+        model: &AssetModel,
+        animator: &Animator,
+        parent: Matrix,
+    ) -> anyhow::Result<Matrix> {
+        let bone = animator.get_bone_data(model, &self.bone)?;
+
+        self.get_transform(bone, parent)
+    }
+
+    /// Get the world point of a transform given by the attachment, for lights and effects.
+    pub fn get_point(transform: Matrix) -> Vector3 {
+        Vector3::zero().transform_with(transform)
+    }
+
+    fn get_transform(
+        &self,
+        bone: Option<(Vector3, Vector4, Vector3)>,
+        parent: Matrix,
+    ) -> anyhow::Result<Matrix> {
+        let (point, angle, scale) = bone.ok_or(anyhow::Error::msg(format!(
+            "Attachment::get_transform(): Could not find bone \"{}\".",
+            self.bone
+        )))?;
+
+        let offset = Matrix::rotate_xyz(Vector3::new(
+            self.angle.x.to_radians(),
+            self.angle.y.to_radians(),
+            self.angle.z.to_radians(),
+        )) * Matrix::translate(self.point.x, self.point.y, self.point.z);
+        let bone = (Matrix::scale(scale.x, scale.y, scale.z) * angle.to_matrix())
+            * Matrix::translate(point.x, point.y, point.z);
+
+        Ok(offset * bone * parent)
     }
 }
 
@@ -230,15 +296,17 @@ pub struct Direction {
 
 impl Direction {
     pub fn draw_debug(
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        debug: &mut DebugDraw,
+        category: DebugCategory,
         point: Vector3,
         angle: Vector3,
+        life: f32,
     ) {
         let direction = Self::new_from_angle(&angle);
 
-        draw.draw_ray(Ray::new(point, direction.x), Color::RED);
-        draw.draw_ray(Ray::new(point, direction.y), Color::GREEN);
-        draw.draw_ray(Ray::new(point, direction.z), Color::BLUE);
+        debug.arrow(category, point, point + direction.x, Color::RED, life);
+        debug.arrow(category, point, point + direction.y, Color::GREEN, life);
+        debug.arrow(category, point, point + direction.z, Color::BLUE, life);
     }
 
     pub fn new_from_angle(angle: &Vector3) -> Self {
@@ -300,9 +368,7 @@ impl Target {
     }
 
     /// Blend this target's data to another target's data.
-    pub fn blend(&mut self, handle: &RaylibHandle, target: &Target) {
-        let frame = handle.get_frame_time();
-
+    pub fn blend(&mut self, frame: f32, target: &Target) {
         self.point += (target.point - self.point) * frame * Self::POINT_SPEED;
         self.angle += (target.angle - self.angle) * frame * Self::ANGLE_SPEED;
         self.scale += (target.scale - self.scale) * frame * Self::SCALE_SPEED;
@@ -311,6 +377,102 @@ impl Target {
 
 //================================================================
 
+/// The view frustum of a perspective camera, as six inward-facing planes.
+pub struct Frustum {
+    /// Plane list, as (normal, distance).
+    plane: [Vector4; 6],
+}
+
+impl Frustum {
+    /// Near clip distance, as used by RL.
+    const CLIP_NEAR: f32 = 0.05;
+    /// Far clip distance, as used by RL.
+    const CLIP_FAR: f32 = 4000.0;
+
+    /// Create a new frustum from a perspective camera and a screen aspect ratio.
+    pub fn new(camera: Camera3D, aspect: f32) -> Self {
+        let view = raylib::math::Matrix::look_at(camera.position, camera.target, camera.up);
+        let projection = raylib::math::Matrix::perspective(
+            camera.fovy.to_radians(),
+            aspect,
+            Self::CLIP_NEAR,
+            Self::CLIP_FAR,
+        );
+        let m = view * projection;
+
+        // each plane is the sum or difference of the "w" row and another row of the clip matrix.
+        let x = [m.m0, m.m4, m.m8, m.m12];
+        let y = [m.m1, m.m5, m.m9, m.m13];
+        let z = [m.m2, m.m6, m.m10, m.m14];
+        let w = [m.m3, m.m7, m.m11, m.m15];
+        let plane = |row: [f32; 4], sign: f32| {
+            Vector4::new(
+                w[0] + row[0] * sign,
+                w[1] + row[1] * sign,
+                w[2] + row[2] * sign,
+                w[3] + row[3] * sign,
+            )
+        };
+
+        Self {
+            plane: [
+                plane(x, 1.0),
+                plane(x, -1.0),
+                plane(y, 1.0),
+                plane(y, -1.0),
+                plane(z, 1.0),
+                plane(z, -1.0),
+            ],
+        }
+    }
+
+    /// Check if a box is at least partially inside of the frustum.
+    pub fn has_box(&self, bound: BoundingBox) -> bool {
+        self.plane.iter().all(|plane| {
+            // the box corner furthest along the plane's normal.
+            let pick = |normal: f32, min: f32, max: f32| if normal >= 0.0 { max } else { min };
+            let point = Vector3::new(
+                pick(plane.x, bound.min.x, bound.max.x),
+                pick(plane.y, bound.min.y, bound.max.y),
+                pick(plane.z, bound.min.z, bound.max.z),
+            );
+
+            plane.x * point.x + plane.y * point.y + plane.z * point.z + plane.w >= 0.0
+        })
+    }
+}
+
+//================================================================
+
+/// A JSON definition file, such as a particle definition or a camera path. Shared by the engine's
+/// loaders and the offline checker, so that both accept the exact same files.
+pub trait Definition: DeserializeOwned {
+    /// Check the definition for any value the engine can not use.
+    fn validate(&self) -> Result<(), String>;
+
+    /// Load a definition file, and check it.
+    fn load(path: &str) -> anyhow::Result<Self> {
+        let file = std::fs::read_to_string(path).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "Definition::load(): Could not read file \"{path}\": {error}"
+            ))
+        })?;
+        let data: Self = serde_json::from_str(&file).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "Definition::load(): Could not parse file \"{path}\": {error}"
+            ))
+        })?;
+
+        data.validate().map_err(|error| {
+            anyhow::Error::msg(format!("Definition::load(): \"{path}\": {error}"))
+        })?;
+
+        Ok(data)
+    }
+}
+
+//================================================================
+
 pub fn movement_walk(
     physical: &mut Physical,
     collider: ColliderHandle,
@@ -417,6 +579,17 @@ pub fn movement_walk(
     Ok(())
 }
 
+/// Convert a fixed-size, null-terminated C string (such as a bone or animation name) to a string.
+pub fn name_from_raw(name: &[std::ffi::c_char]) -> String {
+    let name: Vec<u8> = name
+        .iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as u8)
+        .collect();
+
+    String::from_utf8_lossy(&name).to_string()
+}
+
 pub fn draw_model_transform(
     draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
     model: &mut AssetModel,
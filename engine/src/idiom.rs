@@ -0,0 +1,425 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+use crate::helper::*;
+use crate::user::*;
+
+//================================================================
+
+use hashbrown::{HashMap, HashSet};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::fmt::Display;
+
+//================================================================
+
+/// Look up a string by key, with optional format arguments. A "count" argument, if given first,
+/// also picks the plural form of the string.
+///
+/// ```ignore
+/// idiom!(app.view.idiom, "main.begin");
+/// idiom!(app.view.idiom, "logger.idiom_missing", count = list.len(), idiom = kind);
+/// ```
+macro_rules! idiom {
+    ($idiom:expr, $key:expr) => {
+        $idiom.get($key)
+    };
+    ($idiom:expr, $key:expr, count = $count:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $idiom.get_plural(
+            $key,
+            $count as i64,
+            &[
+                ("count", &$count as &dyn std::fmt::Display),
+                $((stringify!($name), &$value as &dyn std::fmt::Display)),*
+            ],
+        )
+    };
+    ($idiom:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $idiom.get_format(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+pub(crate) use idiom;
+
+//================================================================
+
+/// A string table entry: a single string, or one string for each plural form.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum IdiomText {
+    Text(String),
+    Plural { one: String, other: String },
+}
+
+impl IdiomText {
+    /// Get the string for a count, in a given idiom. A string with no plural form ignores the count.
+    fn get(&self, kind: IdiomKind, count: i64) -> &str {
+        match self {
+            Self::Text(text) => text,
+            Self::Plural { one, other } => match kind {
+                // both idioms only use the singular form for a count of one.
+                IdiomKind::English | IdiomKind::Spanish => {
+                    if count == 1 {
+                        one
+                    } else {
+                        other
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// String table, from a "data/idiom/{idiom}.json" file: every string, by key.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(transparent)]
+pub struct IdiomTable {
+    pub map: HashMap<String, IdiomText>,
+}
+
+impl IdiomTable {
+    /// Path to the string table folder.
+    const PATH: &str = "data/idiom";
+    /// Built-in English fallback, for the few keys needed to leave the main menu and report a
+    /// missing key, should the English table file be missing or broken.
+    const BASE: &str = r#"{
+        "main.begin": "begin",
+        "main.setup": "setup",
+        "main.close": "close",
+        "setup.return": "return",
+        "logger.idiom_missing": {
+            "one": "{count} key missing from the {idiom} string table.",
+            "other": "{count} keys missing from the {idiom} string table."
+        }
+    }"#;
+
+    /// Load the string table file for an idiom. A missing file is treated as an empty table.
+    pub fn new(kind: IdiomKind) -> anyhow::Result<Self> {
+        let path = Self::get_path(kind);
+
+        if !std::path::Path::new(&path).is_file() {
+            return Ok(Self::default());
+        }
+
+        Self::load(&path)
+    }
+
+    /// Get the built-in English string table.
+    pub fn new_base() -> Self {
+        serde_json::from_str(Self::BASE).unwrap()
+    }
+
+    /// Get the path to the string table file for an idiom.
+    pub fn get_path(kind: IdiomKind) -> String {
+        format!("{}/{}.json", Self::PATH, kind.folder_name())
+    }
+}
+
+impl Definition for IdiomTable {
+    fn validate(&self) -> Result<(), String> {
+        for (key, text) in &self.map {
+            let empty = match text {
+                IdiomText::Text(text) => text.is_empty(),
+                IdiomText::Plural { one, other } => one.is_empty() || other.is_empty(),
+            };
+
+            if empty {
+                return Err(format!("\"{key}\": Text is empty."));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// Localized string look-up, for the active idiom. A key missing from the active idiom's table
+/// falls back to the English table file, then to the built-in English table, then to the key
+/// itself.
+pub struct Idiom {
+    /// Active idiom. None until the first call to "set_idiom".
+    kind: Option<IdiomKind>,
+    /// Built-in English table.
+    base: IdiomTable,
+    /// English table file.
+    english: IdiomTable,
+    /// Active idiom table file.
+    table: IdiomTable,
+    /// Keys looked up and found in no table at all.
+    missing: RefCell<HashSet<String>>,
+}
+
+impl Idiom {
+    /// Switch to an idiom, loading its string table. Returns true if the idiom changed, or false
+    /// if it was already active. The idiom is switched to even if a table fails to load, with every
+    /// key falling back to the built-in table, so that a broken table is only reported once.
+    pub fn set_idiom(&mut self, kind: IdiomKind) -> anyhow::Result<bool> {
+        if self.kind == Some(kind) {
+            return Ok(false);
+        }
+
+        self.kind = Some(kind);
+        self.english = IdiomTable::default();
+        self.table = IdiomTable::default();
+
+        self.english = IdiomTable::new(IdiomKind::English)?;
+        self.table = if kind == IdiomKind::English {
+            self.english.clone()
+        } else {
+            IdiomTable::new(kind)?
+        };
+
+        Ok(true)
+    }
+
+    /// Get a string by key.
+    pub fn get(&self, key: &str) -> String {
+        self.get_format(key, &[])
+    }
+
+    /// Get a string by key, replacing every "{name}" in it with the argument of that name.
+    pub fn get_format(&self, key: &str, argument: &[(&str, &dyn Display)]) -> String {
+        self.get_plural(key, 1, argument)
+    }
+
+    /// Get a string by key, in the plural form for a count, replacing every "{name}" in it with
+    /// the argument of that name.
+    pub fn get_plural(&self, key: &str, count: i64, argument: &[(&str, &dyn Display)]) -> String {
+        let kind = self.kind.unwrap_or(IdiomKind::English);
+
+        let Some(text) = self.find(key) else {
+            self.missing.borrow_mut().insert(key.to_string());
+            return key.to_string();
+        };
+
+        let mut text = text.get(kind, count).to_string();
+
+        for (name, value) in argument {
+            text = text.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+
+        text
+    }
+
+    /// Get the string for an enumerator value, by the "value.{name}" key, where the name is the
+    /// value's display text in snake case. Values with no key, such as brand names, are shown as
+    /// they are.
+    pub fn get_value(&self, value: &dyn Display) -> String {
+        let text = value.to_string();
+        let key = format!("value.{}", text.to_lowercase().replace(' ', "_"));
+
+        match self.find(&key) {
+            Some(entry) => entry
+                .get(self.kind.unwrap_or(IdiomKind::English), 1)
+                .to_string(),
+            None => text,
+        }
+    }
+
+    /// Get every key missing from the active idiom's table, sorted. This is every built-in or
+    /// English key the table does not have, and every key looked up but found in no table.
+    pub fn get_missing(&self) -> Vec<String> {
+        let mut list: Vec<String> = self
+            .base
+            .map
+            .keys()
+            .chain(self.english.map.keys())
+            .filter(|key| !self.table.map.contains_key(*key))
+            .cloned()
+            .chain(self.missing.borrow().iter().cloned())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        list.sort();
+
+        list
+    }
+
+    /// Get every code point in use by the active idiom: every character in every string table,
+    /// and the idiom's alphabet, for any text not in a table, such as a subtitle.
+    pub fn get_code_point(&self) -> String {
+        let mut code: String = self.get_kind().alphabet().to_string();
+
+        for text in self
+            .base
+            .map
+            .values()
+            .chain(self.english.map.values())
+            .chain(self.table.map.values())
+        {
+            match text {
+                IdiomText::Text(text) => code.push_str(text),
+                IdiomText::Plural { one, other } => {
+                    code.push_str(one);
+                    code.push_str(other);
+                }
+            }
+        }
+
+        code
+    }
+
+    /// Get the active idiom.
+    pub fn get_kind(&self) -> IdiomKind {
+        self.kind.unwrap_or(IdiomKind::English)
+    }
+
+    fn find(&self, key: &str) -> Option<&IdiomText> {
+        self.table
+            .map
+            .get(key)
+            .or_else(|| self.english.map.get(key))
+            .or_else(|| self.base.map.get(key))
+    }
+}
+
+impl Default for Idiom {
+    fn default() -> Self {
+        Self {
+            kind: None,
+            base: IdiomTable::new_base(),
+            english: IdiomTable::default(),
+            table: IdiomTable::default(),
+            missing: RefCell::default(),
+        }
+    }
+}
+
+//================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Get a string table out of a JSON object.
+    fn table(json: &str) -> IdiomTable {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /// Get a look-up for an idiom, with a given English table file and idiom table file.
+    fn idiom(kind: IdiomKind, english: &str, other: &str) -> Idiom {
+        Idiom {
+            kind: Some(kind),
+            english: table(english),
+            table: table(other),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plural() {
+        let idiom = idiom(
+            IdiomKind::Spanish,
+            "{}",
+            r#"{ "item": { "one": "{count} objeto", "other": "{count} objetos" } }"#,
+        );
+
+        assert_eq!(idiom!(idiom, "item", count = 1), "1 objeto");
+        assert_eq!(idiom!(idiom, "item", count = 0), "0 objetos");
+        assert_eq!(idiom!(idiom, "item", count = 2), "2 objetos");
+    }
+
+    #[test]
+    fn format() {
+        let idiom = idiom(
+            IdiomKind::English,
+            r#"{ "greet": "hello {name}, {name}: {place}" }"#,
+            "{}",
+        );
+
+        assert_eq!(
+            idiom!(idiom, "greet", name = "a", place = 2),
+            "hello a, a: 2"
+        );
+        // a string with no plural form ignores the count.
+        assert_eq!(
+            idiom.get_plural("greet", 5, &[("name", &"b"), ("place", &3)]),
+            "hello b, b: 3"
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let idiom = idiom(
+            IdiomKind::Spanish,
+            r#"{ "a": "english a", "b": "english b", "main.begin": "english begin" }"#,
+            r#"{ "a": "spanish a" }"#,
+        );
+
+        // idiom table, then English table file, then built-in table, then the key itself.
+        assert_eq!(idiom.get("a"), "spanish a");
+        assert_eq!(idiom.get("b"), "english b");
+        assert_eq!(idiom.get("main.begin"), "english begin");
+        assert_eq!(idiom.get("main.close"), "close");
+        assert_eq!(idiom.get("c"), "c");
+    }
+
+    #[test]
+    fn missing() {
+        let idiom = idiom(
+            IdiomKind::Spanish,
+            r#"{ "a": "english a", "b": "english b" }"#,
+            r#"{ "a": "spanish a", "main.begin": "comenzar", "main.setup": "opciones",
+                "main.close": "salir", "setup.return": "volver",
+                "logger.idiom_missing": { "one": "uno", "other": "otro" } }"#,
+        );
+
+        assert_eq!(idiom.get_missing(), vec!["b".to_string()]);
+
+        idiom.get("c");
+        idiom.get("c");
+
+        assert_eq!(idiom.get_missing(), vec!["b".to_string(), "c".to_string()]);
+    }
+}
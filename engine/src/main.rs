@@ -48,26 +48,8 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
-// TO-DO clean up ray/shape casting for entity API
-// TO-DO AI pathing system
-// TO-DO level generation system
-// TO-DO look into creating own custom model format for level model/entity model
-// TO-DO translation system
-// TO-DO use .zip for data/ folder
-
-mod app;
-mod asset;
-mod entity;
-mod helper;
-mod physical;
-mod scene;
-mod user;
-mod view;
-mod world;
-
-//================================================================
-
-use crate::app::*;
+use engine::app::*;
+use engine::capture::*;
 
 //================================================================
 
@@ -77,5 +59,11 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 //================================================================
 
 fn main() {
-    App::main().unwrap()
+    let argument: Vec<String> = std::env::args().skip(1).collect();
+
+    if argument.first().is_some_and(|entry| entry == "--capture") {
+        Capture::main(&argument[1..]).unwrap()
+    } else {
+        App::main().unwrap()
+    }
 }
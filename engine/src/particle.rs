@@ -0,0 +1,585 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::asset::*;
+use crate::helper::*;
+
+//================================================================
+
+use hashbrown::{HashMap, HashSet};
+use rand::Rng;
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// Particle emitter mode.
+#[derive(Debug, Deserialize, Copy, Clone)]
+#[serde(tag = "type")]
+pub enum EmitterMode {
+    /// Spawn "count" particles at once, when attached.
+    Burst { count: usize },
+    /// Spawn "rate" particles per second, for "length" seconds (or until detached if none).
+    Continuous { rate: f32, length: Option<f32> },
+    /// Spawn "count" particles each time the emitter is triggered.
+    Event { count: usize },
+}
+
+/// Particle definition, from a "data/particle/*.json" file.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ParticleData {
+    /// Path to the atlas file.
+    pub atlas: String,
+    /// Atlas sequence to play over each particle's life-time.
+    pub sequence: String,
+    /// Emitter mode.
+    pub emitter: EmitterMode,
+    /// Life-time range, in seconds.
+    pub life: [f32; 2],
+    /// Spawn point offset range, from the emitter point.
+    #[serde(default)]
+    pub offset: [Vector3; 2],
+    /// Initial velocity range.
+    #[serde(default)]
+    pub velocity: [Vector3; 2],
+    /// Constant acceleration.
+    #[serde(default)]
+    pub gravity: Vector3,
+    /// Velocity drag, per second.
+    #[serde(default)]
+    pub drag: f32,
+    /// Size over life-time. Keys are evenly spaced from birth to death.
+    pub size: Vec<f32>,
+    /// Color over life-time. Keys are evenly spaced from birth to death.
+    pub color: Vec<Color>,
+    /// Initial rotation range, in degrees.
+    #[serde(default)]
+    pub rotation: [f32; 2],
+    /// Rotation speed range, in degrees per second.
+    #[serde(default)]
+    pub spin: [f32; 2],
+}
+
+impl ParticleData {
+    /// Path to the particle definition folder.
+    const PATH: &str = "data/particle";
+
+    /// Load a particle definition by name.
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        Self::load(&Self::get_path(name))
+    }
+
+    /// Get the path to a particle definition by name.
+    pub fn get_path(name: &str) -> String {
+        format!("{}/{name}.json", Self::PATH)
+    }
+
+    /// Spawn a new particle at a given point.
+    fn spawn(&self, point: Vector3) -> ParticleNode {
+        let mut random = rand::rng();
+        let mut range = |range: [f32; 2]| {
+            if range[0] < range[1] {
+                random.random_range(range[0]..range[1])
+            } else {
+                range[0]
+            }
+        };
+        let mut range_vector = |range_list: [Vector3; 2]| {
+            Vector3::new(
+                range([range_list[0].x, range_list[1].x]),
+                range([range_list[0].y, range_list[1].y]),
+                range([range_list[0].z, range_list[1].z]),
+            )
+        };
+
+        let offset = range_vector(self.offset);
+        let velocity = range_vector(self.velocity);
+
+        ParticleNode {
+            point: point + offset,
+            velocity,
+            rotation: range(self.rotation),
+            spin: range(self.spin),
+            time: 0.0,
+            life: range(self.life),
+        }
+    }
+
+    /// Sample the size curve at a given life-time fraction.
+    fn get_size(&self, time: f32) -> f32 {
+        let (a, b, blend) = Self::get_key(&self.size, time);
+
+        a + (b - a) * blend
+    }
+
+    /// Sample the color curve at a given life-time fraction.
+    fn get_color(&self, time: f32) -> Color {
+        let (a, b, blend) = Self::get_key(&self.color, time);
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * blend) as u8;
+
+        Color::new(
+            lerp(a.r, b.r),
+            lerp(a.g, b.g),
+            lerp(a.b, b.b),
+            lerp(a.a, b.a),
+        )
+    }
+
+    /// Get the two keys around a given life-time fraction, and the blend between them.
+    fn get_key<T: Copy>(list: &[T], time: f32) -> (T, T, f32) {
+        let point = time.clamp(0.0, 1.0) * (list.len() - 1) as f32;
+        let index = (point as usize).min(list.len() - 1);
+        let next = (index + 1).min(list.len() - 1);
+
+        (list[index], list[next], point - index as f32)
+    }
+}
+
+impl Definition for ParticleData {
+    fn validate(&self) -> Result<(), String> {
+        if self.size.is_empty() {
+            return Err("Size curve has no key.".to_string());
+        }
+
+        if self.color.is_empty() {
+            return Err("Color curve has no key.".to_string());
+        }
+
+        if self.life[0] <= 0.0 || self.life[1] < self.life[0] {
+            return Err("Life-time range must be positive and ordered.".to_string());
+        }
+
+        if let EmitterMode::Continuous { rate, .. } = self.emitter
+            && rate <= 0.0
+        {
+            return Err("Continuous emitter rate must be positive.".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// A single particle.
+struct ParticleNode {
+    point: Vector3,
+    velocity: Vector3,
+    /// Rotation, in degrees.
+    rotation: f32,
+    /// Rotation speed, in degrees per second.
+    spin: f32,
+    /// Time (in seconds) since the particle was spawned.
+    time: f32,
+    /// Life-time, in seconds.
+    life: f32,
+}
+
+/// Handle to an attached emitter. A handle is only valid for the emitter it was given for: once
+/// that emitter is removed, the handle no longer refers to any emitter, even if its slot is taken
+/// by another one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct EmitterHandle {
+    index: usize,
+    order: usize,
+}
+
+/// A particle emitter, spawning particles from a particle definition.
+struct Emitter {
+    /// Particle definition name.
+    name: String,
+    /// Attach order, to check a handle against.
+    order: usize,
+    point: Vector3,
+    /// Time (in seconds) since the emitter was attached.
+    time: f32,
+    /// Fractional particle count left over from the last continuous spawn.
+    spawn: f32,
+    /// Burst emitters only spawn once, on the first update.
+    start: bool,
+    /// Trigger count since the last update, for on-event emitters.
+    trigger: usize,
+    /// Stop spawning, and remove the emitter once every particle is dead.
+    detach: bool,
+    node_list: Vec<ParticleNode>,
+}
+
+/// Particle system. Emitters are simulated on the CPU, and every particle with the same atlas is
+/// drawn as a single batch of billboards.
+#[derive(Default)]
+pub struct ParticleSystem {
+    /// Particle definition, by name. Definitions are loaded on the first update after an emitter
+    /// using them is attached.
+    data: HashMap<String, ParticleData>,
+    /// Particle definition that could not be loaded, by name. Emitters using them are removed.
+    miss: HashSet<String>,
+    /// Failure list since the last call to "take_failure".
+    failure: Vec<String>,
+    /// Emitter slot list. Removed emitters leave an empty slot so indices stay valid, and a handle
+    /// is checked against the attach order of its slot's emitter.
+    emitter_list: Vec<Option<Emitter>>,
+    /// Attach count, for the attach order of each emitter.
+    order: usize,
+}
+
+impl ParticleSystem {
+    /// Attach a new emitter from a particle definition name. Returns the handle to the emitter.
+    pub fn attach(&mut self, name: &str, point: Vector3) -> EmitterHandle {
+        let order = self.order;
+        let emitter = Emitter {
+            name: name.to_string(),
+            order,
+            point,
+            time: 0.0,
+            spawn: 0.0,
+            start: false,
+            trigger: 0,
+            detach: false,
+            node_list: Vec::default(),
+        };
+
+        self.order += 1;
+
+        let index = if let Some(index) = self.emitter_list.iter().position(|slot| slot.is_none()) {
+            self.emitter_list[index] = Some(emitter);
+            index
+        } else {
+            self.emitter_list.push(Some(emitter));
+            self.emitter_list.len() - 1
+        };
+
+        EmitterHandle { index, order }
+    }
+
+    /// Attach a new emitter which will be removed on its own once every particle is dead, such as
+    /// for a one-off burst.
+    pub fn spawn(&mut self, name: &str, point: Vector3) {
+        let handle = self.attach(name, point);
+
+        self.emitter_list[handle.index].as_mut().unwrap().detach = true;
+    }
+
+    /// Stop an emitter. Live particles will finish their life-time before the emitter is removed.
+    pub fn detach(&mut self, handle: EmitterHandle) -> anyhow::Result<()> {
+        self.get_mutable(handle)?.detach = true;
+
+        Ok(())
+    }
+
+    /// Set the point of an emitter. Live particles are not moved.
+    pub fn set_point(&mut self, handle: EmitterHandle, point: Vector3) -> anyhow::Result<()> {
+        self.get_mutable(handle)?.point = point;
+
+        Ok(())
+    }
+
+    /// Trigger an on-event emitter. Other emitter modes will ignore the trigger.
+    pub fn trigger(&mut self, handle: EmitterHandle) -> anyhow::Result<()> {
+        self.get_mutable(handle)?.trigger += 1;
+
+        Ok(())
+    }
+
+    /// Clear every emitter and every particle.
+    pub fn clear(&mut self) {
+        self.emitter_list.clear();
+    }
+
+    fn load(asset: &mut Asset, context: &mut Context, name: &str) -> anyhow::Result<ParticleData> {
+        let data = ParticleData::new(name)?;
+
+        asset.set_atlas(context, &data.atlas)?;
+        asset.validate_sequence(&data.atlas, &data.sequence)?;
+
+        Ok(data)
+    }
+
+    fn get_mutable(&mut self, handle: EmitterHandle) -> anyhow::Result<&mut Emitter> {
+        self.emitter_list
+            .get_mut(handle.index)
+            .and_then(|slot| slot.as_mut())
+            .filter(|emitter| emitter.order == handle.order)
+            .ok_or(anyhow::Error::msg(format!(
+                "ParticleSystem::get_mutable(): Could not find emitter \"{}\".",
+                handle.index
+            )))
+    }
+
+    /// Take every failure since the last call, such as a particle definition that could not be
+    /// loaded.
+    pub fn take_failure(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.failure)
+    }
+
+    /// Load any missing particle definition, then simulate every emitter and particle. An emitter
+    /// with a definition that could not be loaded is removed, and the failure is kept for
+    /// "take_failure".
+    pub fn update(
+        &mut self,
+        asset: &mut Asset,
+        context: &mut Context,
+        time: f32,
+    ) -> anyhow::Result<()> {
+        for emitter in self.emitter_list.iter().flatten() {
+            if !self.data.contains_key(&emitter.name) && !self.miss.contains(&emitter.name) {
+                match Self::load(asset, context, &emitter.name) {
+                    Ok(data) => {
+                        self.data.insert(emitter.name.clone(), data);
+                    }
+                    Err(error) => {
+                        self.failure.push(format!(
+                            "ParticleSystem::update(): Could not load particle \"{}\": {error}",
+                            emitter.name
+                        ));
+                        self.miss.insert(emitter.name.clone());
+                    }
+                }
+            }
+        }
+
+        for slot in &mut self.emitter_list {
+            if let Some(emitter) = slot
+                && self.miss.contains(&emitter.name)
+            {
+                *slot = None;
+            }
+        }
+
+        for slot in &mut self.emitter_list {
+            let Some(emitter) = slot else {
+                continue;
+            };
+            let data = &self.data[&emitter.name];
+
+            // spawn.
+            let count = match data.emitter {
+                EmitterMode::Burst { count } => {
+                    if emitter.start {
+                        0
+                    } else {
+                        count
+                    }
+                }
+                EmitterMode::Continuous { rate, length } => {
+                    let active = length.is_none_or(|length| emitter.time < length);
+
+                    if active && !emitter.detach {
+                        emitter.spawn += rate * time;
+
+                        let count = emitter.spawn as usize;
+                        emitter.spawn = emitter.spawn.fract();
+                        count
+                    } else {
+                        emitter.detach = true;
+                        0
+                    }
+                }
+                EmitterMode::Event { count } => emitter.trigger * count,
+            };
+
+            for _ in 0..count {
+                emitter.node_list.push(data.spawn(emitter.point));
+            }
+
+            emitter.start = true;
+            emitter.trigger = 0;
+            emitter.time += time;
+
+            // simulate.
+            let drag = (1.0 - data.drag * time).max(0.0);
+
+            for node in &mut emitter.node_list {
+                node.velocity = (node.velocity + data.gravity * time) * drag;
+                node.point += node.velocity * time;
+                node.rotation += node.spin * time;
+                node.time += time;
+            }
+
+            emitter.node_list.retain(|node| node.time < node.life);
+
+            let finish = match data.emitter {
+                EmitterMode::Burst { .. } => true,
+                _ => emitter.detach,
+            };
+
+            if finish && emitter.node_list.is_empty() {
+                *slot = None;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw every particle as a camera-facing billboard. Particles are batched by atlas texture.
+    pub fn draw(
+        &self,
+        _: &mut impl RaylibDraw3D,
+        camera: &Camera3D,
+        asset: &Asset,
+    ) -> anyhow::Result<()> {
+        let forward = (camera.target - camera.position).normalized();
+        let right = forward.cross(camera.up).normalized();
+        let up = right.cross(forward);
+
+        let mut batch: HashMap<&str, Vec<(&Emitter, &ParticleData)>> = HashMap::default();
+
+        for emitter in self.emitter_list.iter().flatten() {
+            if let Some(data) = self.data.get(&emitter.name) {
+                batch
+                    .entry(data.atlas.as_str())
+                    .or_default()
+                    .push((emitter, data));
+            }
+        }
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlDisableDepthMask();
+        }
+
+        let mut result = Ok(());
+
+        for (atlas, emitter_list) in batch {
+            let sequence = &emitter_list[0].1.sequence;
+            let texture = match asset.get_sprite_sequence(atlas, sequence, 0.0) {
+                Ok(sprite) => sprite.texture,
+                Err(error) => {
+                    result = Err(error);
+                    break;
+                }
+            };
+
+            unsafe {
+                ffi::rlSetTexture(texture.id);
+                ffi::rlBegin(ffi::RL_QUADS as i32);
+            }
+
+            result = Self::draw_atlas(asset, atlas, &emitter_list, texture, right, up);
+
+            // the batch is closed, and the depth mask restored, even on failure.
+            unsafe {
+                ffi::rlEnd();
+                ffi::rlSetTexture(0);
+            }
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlEnableDepthMask();
+        }
+
+        result
+    }
+
+    /// Push every particle using an atlas into an open quad batch.
+    fn draw_atlas<'a>(
+        asset: &'a Asset,
+        atlas: &str,
+        emitter_list: &[(&Emitter, &ParticleData)],
+        mut texture: &'a Texture2D,
+        right: Vector3,
+        up: Vector3,
+    ) -> anyhow::Result<()> {
+        for (emitter, data) in emitter_list {
+            for node in &emitter.node_list {
+                let life = node.time / node.life;
+                let sprite = asset.get_sprite_sequence(atlas, &data.sequence, node.time)?;
+
+                // an atlas made out of loose image files has one texture per frame.
+                if sprite.texture.id != texture.id {
+                    texture = sprite.texture;
+
+                    unsafe {
+                        ffi::rlEnd();
+                        ffi::rlSetTexture(texture.id);
+                        ffi::rlBegin(ffi::RL_QUADS as i32);
+                    }
+                }
+
+                let texture_size = Vector2::new(texture.width as f32, texture.height as f32);
+                let size = data.get_size(life);
+                let color = data.get_color(life);
+
+                let (sin, cos) = node.rotation.to_radians().sin_cos();
+                let aspect = sprite.shape.width / sprite.shape.height;
+                let axis_x = (right * cos + up * sin) * size * aspect * 0.5;
+                let axis_y = (up * cos - right * sin) * size * 0.5;
+
+                let u_a = sprite.shape.x / texture_size.x;
+                let v_a = sprite.shape.y / texture_size.y;
+                let u_b = (sprite.shape.x + sprite.shape.width) / texture_size.x;
+                let v_b = (sprite.shape.y + sprite.shape.height) / texture_size.y;
+
+                let corner = [
+                    (node.point - axis_x - axis_y, u_a, v_b),
+                    (node.point + axis_x - axis_y, u_b, v_b),
+                    (node.point + axis_x + axis_y, u_b, v_a),
+                    (node.point - axis_x + axis_y, u_a, v_a),
+                ];
+
+                unsafe {
+                    ffi::rlColor4ub(color.r, color.g, color.b, color.a);
+
+                    for (point, u, v) in corner {
+                        ffi::rlTexCoord2f(u, v);
+                        ffi::rlVertex3f(point.x, point.y, point.z);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -48,6 +48,8 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::asset::SurfaceKind;
+use crate::debug::*;
 use crate::entity::implementation::*;
 use crate::world::*;
 
@@ -64,6 +66,21 @@ use std::sync::Mutex;
 
 //================================================================
 
+/// Ray-cast hit data, from "Physical::cast_ray_surface".
+#[derive(Debug, Copy, Clone)]
+pub struct SurfaceHit {
+    /// Collider that was hit.
+    pub collider: ColliderHandle,
+    /// Hit point, in world space.
+    pub point: Vector3,
+    /// Surface normal at the hit point.
+    pub normal: Vector3,
+    /// Distance from the ray origin to the hit point, in units of the ray direction.
+    pub distance: f32,
+    /// Surface kind. Only geometry colliders have a surface kind other than the default.
+    pub surface: SurfaceKind,
+}
+
 #[derive(Default, Copy, Clone)]
 pub struct Presence {
     pub rigid: RigidBodyHandle,
@@ -166,6 +183,11 @@ impl Physical {
     pub const GROUP_GEOMETRY: Group = Group::GROUP_3;
     pub const GROUP_GEOMETRY_SENSOR: Group = Group::GROUP_4;
 
+    /// Get the rigid body and collider count.
+    pub fn get_body_count(&self) -> (usize, usize) {
+        (self.rigid_body_set.len(), self.collider_set.len())
+    }
+
     /// Run a tick in the physical simulation.
     pub fn tick(&mut self) {
         if let Ok(lock) = &mut self.collision_handler.collision_list.lock() {
@@ -188,10 +210,10 @@ impl Physical {
         );
     }
 
-    /// Draw the physical simulation's state.
-    pub fn draw(&mut self) {
+    /// Push the physical simulation's state to the debug draw, for the current tick.
+    pub fn draw(&mut self, debug: &mut DebugDraw) {
         self.debug_render_pipeline.render(
-            &mut DebugRender {},
+            &mut DebugRender { debug },
             &self.rigid_body_set,
             &self.collider_set,
             &self.impulse_joint_set,
@@ -275,6 +297,48 @@ impl Physical {
         None
     }
 
+    /// Get every collider intersecting a cuboid in the world.
+    pub fn intersect_cuboid_list(
+        &self,
+        point: Vector3,
+        angle: Vector3,
+        shape: Vector3,
+        rigid: Option<RigidBodyHandle>,
+        filter: QueryFilter,
+    ) -> Vec<ColliderHandle> {
+        let filter = if let Some(rigid) = rigid {
+            filter.exclude_rigid_body(rigid)
+        } else {
+            filter
+        };
+
+        let (v, a) = Vector4::from_euler(
+            angle.y.to_radians(),
+            angle.x.to_radians(),
+            angle.z.to_radians(),
+        )
+        .to_axis_angle();
+        let angle = v * a;
+
+        let point = Isometry::new(
+            vector![point.x, point.y, point.z],
+            vector![angle.x, angle.y, angle.z],
+        );
+        let shape = Cuboid::new(vector![shape.x, shape.y, shape.z]);
+
+        let query_pipeline = self.broad_phase.as_query_pipeline(
+            self.narrow_phase.query_dispatcher(),
+            &self.rigid_body_set,
+            &self.collider_set,
+            filter,
+        );
+
+        query_pipeline
+            .intersect_shape(point, &shape)
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
     /// Cast a ray in the world.
     pub fn cast_ray(
         &self,
@@ -306,6 +370,26 @@ impl Physical {
         query_pipeline.cast_ray_and_get_normal(&ray, distance, solid)
     }
 
+    /// Cast a ray in the world, and get the hit point, normal and surface kind.
+    pub fn cast_ray_surface(
+        &self,
+        point: Vector3,
+        angle: Vector3,
+        distance: f32,
+        rigid: Option<RigidBodyHandle>,
+        filter: QueryFilter,
+    ) -> Option<SurfaceHit> {
+        let (collider, hit) = self.cast_ray(point, angle, distance, true, rigid, filter)?;
+
+        Some(SurfaceHit {
+            collider,
+            point: point + angle * hit.time_of_impact,
+            normal: Vector3::new(hit.normal.x, hit.normal.y, hit.normal.z),
+            distance: hit.time_of_impact,
+            surface: self.get_collider_surface(collider).unwrap_or_default(),
+        })
+    }
+
     /// Cast a cuboid in the world.
     pub fn cast_cuboid(
         &self,
@@ -559,13 +643,14 @@ impl Physical {
         }
     }
 
-    // Create a new model collider.
+    // Create a new model collider. Each mesh collider will have its surface kind set as user-data.
     pub fn new_model(
         &mut self,
         model: &Model,
+        surface: &[SurfaceKind],
         parent: Option<RigidBodyHandle>,
     ) -> anyhow::Result<()> {
-        for mesh in model.meshes() {
+        for (i, mesh) in model.meshes().iter().enumerate() {
             let list_vertex = mesh
                 .vertices()
                 .iter()
@@ -587,9 +672,12 @@ impl Physical {
                 ]);
             }
 
-            let collider = ColliderBuilder::trimesh(list_vertex, list_index)?.collision_groups(
-                InteractionGroups::new(Self::GROUP_GEOMETRY, Self::GROUP_ENTITY),
-            );
+            let collider = ColliderBuilder::trimesh(list_vertex, list_index)?
+                .collision_groups(InteractionGroups::new(
+                    Self::GROUP_GEOMETRY,
+                    Self::GROUP_ENTITY,
+                ))
+                .user_data(surface.get(i).copied().unwrap_or_default() as u128);
 
             if let Some(parent) = parent {
                 self.collider_set
@@ -691,6 +779,17 @@ impl Physical {
         Ok(self.get_collider(handle)?.user_data)
     }
 
+    /// Get a geometry collider's surface kind.
+    pub fn get_collider_surface(&self, handle: ColliderHandle) -> anyhow::Result<SurfaceKind> {
+        let collider = self.get_collider(handle)?;
+
+        if collider.collision_groups().memberships == Self::GROUP_GEOMETRY {
+            Ok(SurfaceKind::from_data(collider.user_data))
+        } else {
+            Ok(SurfaceKind::default())
+        }
+    }
+
     /// Set a collider's user-data.
     pub fn set_collider_data(&mut self, handle: ColliderHandle, data: u128) -> anyhow::Result<()> {
         self.get_collider_mutable(handle)?.user_data = data;
@@ -732,16 +831,18 @@ impl EventHandler for CollisionHandler {
 
 //================================================================
 
-struct DebugRender {}
+struct DebugRender<'a> {
+    debug: &'a mut DebugDraw,
+}
 
-impl DebugRenderBackend for DebugRender {
+impl DebugRenderBackend for DebugRender<'_> {
     fn draw_line(&mut self, _: DebugRenderObject, a: Point<f32>, b: Point<f32>, _: DebugColor) {
-        unsafe {
-            ffi::DrawLine3D(
-                Vector3::new(a.x, a.y, a.z).into(),
-                Vector3::new(b.x, b.y, b.z).into(),
-                Color::RED.into(),
-            );
-        }
+        self.debug.line(
+            DebugCategory::Physical,
+            Vector3::new(a.x, a.y, a.z),
+            Vector3::new(b.x, b.y, b.z),
+            Color::RED,
+            0.0,
+        );
     }
 }
@@ -0,0 +1,338 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+use crate::asset::*;
+use crate::user::*;
+
+//================================================================
+
+use hashbrown::HashMap;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+
+//================================================================
+
+/// Every possible post-process pass.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PostKind {
+    /// Base screen pass.
+    Screen,
+    /// Exposure tone-mapping.
+    Tonemap,
+    /// Brightness, contrast and gamma.
+    Grade,
+    /// Screen edge darkening.
+    Vignette,
+    /// Chromatic aberration, driven by damage.
+    Aberration,
+    /// Color-blind simulation or correction.
+    ColorBlind,
+}
+
+impl PostKind {
+    /// Get the asset name of the pass shader.
+    fn name(&self) -> &str {
+        match self {
+            Self::Screen => "screen",
+            Self::Tonemap => "post_tonemap",
+            Self::Grade => "post_grade",
+            Self::Vignette => "post_vignette",
+            Self::Aberration => "post_aberration",
+            Self::ColorBlind => "post_color_blind",
+        }
+    }
+
+    /// Get the path to the pass fragment shader.
+    fn path(&self) -> &str {
+        match self {
+            Self::Screen => "data/shader/screen.fs",
+            Self::Tonemap => "data/shader/post/tonemap.fs",
+            Self::Grade => "data/shader/post/grade.fs",
+            Self::Vignette => "data/shader/post/vignette.fs",
+            Self::Aberration => "data/shader/post/aberration.fs",
+            Self::ColorBlind => "data/shader/post/color_blind.fs",
+        }
+    }
+
+    /// Get the uniform name list of the pass shader.
+    fn uniform(&self) -> &[&'static str] {
+        match self {
+            Self::Screen => &[],
+            Self::Tonemap => &["exposure"],
+            Self::Grade => &["brightness", "contrast", "gamma"],
+            Self::Vignette => &["strength", "radius"],
+            Self::Aberration => &["strength"],
+            Self::ColorBlind => &["kind", "correct"],
+        }
+    }
+}
+
+/// Level post-process setting, from the level file.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct PostSetting {
+    /// Pass list, in draw order. Passes not in the list are never drawn.
+    pub pass: Vec<PostKind>,
+    /// Tone-mapping exposure.
+    pub exposure: f32,
+    /// Vignette strength.
+    pub vignette: f32,
+    /// Vignette radius, from the center of the screen.
+    pub vignette_radius: f32,
+    /// Chromatic aberration strength with no damage.
+    pub aberration: f32,
+}
+
+impl Default for PostSetting {
+    fn default() -> Self {
+        Self {
+            pass: vec![
+                PostKind::Screen,
+                PostKind::Tonemap,
+                PostKind::Grade,
+                PostKind::Vignette,
+                PostKind::Aberration,
+                PostKind::ColorBlind,
+            ],
+            exposure: 1.0,
+            vignette: 0.25,
+            vignette_radius: 0.75,
+            aberration: 0.0,
+        }
+    }
+}
+
+/// Post-process stack. Each pass reads the result of the last pass, ping-ponging between two render
+/// textures; the last pass is drawn to the screen.
+#[derive(Default)]
+pub struct Post {
+    /// Level setting.
+    pub setting: PostSetting,
+    /// Damage value, from 0.0 to 1.0. Drives chromatic aberration, and decays over time.
+    damage: f32,
+    /// Ping-pong render texture list.
+    texture: Vec<RenderTexture2D>,
+    /// Uniform location for each pass.
+    location: HashMap<(PostKind, &'static str), i32>,
+}
+
+impl Post {
+    /// Chromatic aberration strength at full damage.
+    const DAMAGE_ABERRATION: f32 = 0.02;
+    /// Damage decay, per second.
+    const DAMAGE_DECAY: f32 = 2.0;
+
+    /// Load every pass shader, and create the ping-pong render textures.
+    pub fn initialize(
+        &mut self,
+        asset: &mut Asset,
+        context: &mut Context,
+        size: Vector2,
+    ) -> anyhow::Result<()> {
+        for kind in [
+            PostKind::Screen,
+            PostKind::Tonemap,
+            PostKind::Grade,
+            PostKind::Vignette,
+            PostKind::Aberration,
+            PostKind::ColorBlind,
+        ] {
+            let shader = asset.set_shader(
+                context,
+                kind.name(),
+                Some("data/shader/base.vs"),
+                Some(kind.path()),
+            )?;
+
+            for name in kind.uniform() {
+                self.location
+                    .insert((kind, *name), shader.get_shader_location(name));
+            }
+        }
+
+        self.update_resolution(context, size)
+    }
+
+    /// Re-create the ping-pong render textures for a new resolution.
+    pub fn update_resolution(
+        &mut self,
+        context: &mut Context,
+        size: Vector2,
+    ) -> anyhow::Result<()> {
+        self.texture.clear();
+
+        for _ in 0..2 {
+            self.texture.push(context.handle.load_render_texture(
+                &context.thread,
+                size.x as u32,
+                size.y as u32,
+            )?);
+        }
+
+        Ok(())
+    }
+
+    /// Set the damage value. The highest of the current and given value is kept.
+    pub fn set_damage(&mut self, damage: f32) {
+        self.damage = self.damage.max(damage.clamp(0.0, 1.0));
+    }
+
+    /// Draw every active pass, from a source render texture to the screen.
+    pub fn draw(
+        &mut self,
+        asset: &mut Asset,
+        context: &mut Context,
+        draw: &mut RaylibDrawHandle,
+        source: &RenderTexture2D,
+        user: &User,
+        time: f32,
+    ) -> anyhow::Result<()> {
+        self.damage = (self.damage - time * Self::DAMAGE_DECAY).max(0.0);
+
+        let screen = Vector2::new(
+            context.handle.get_screen_width() as f32,
+            context.handle.get_screen_height() as f32,
+        );
+
+        let pass_list: Vec<(PostKind, Vec<f32>)> = if user.debug.post_skip {
+            Vec::default()
+        } else {
+            self.setting
+                .pass
+                .iter()
+                .filter_map(|kind| self.get_value(*kind, user).map(|value| (*kind, value)))
+                .collect()
+        };
+
+        if pass_list.is_empty() {
+            Self::blit(draw, source, screen);
+            return Ok(());
+        }
+
+        let texture = self.texture.as_mut_ptr();
+        let mut read = source as *const RenderTexture2D;
+
+        for (i, (kind, value)) in pass_list.iter().enumerate() {
+            let shader = asset.get_shader(kind.name())?;
+
+            for (name, value) in kind.uniform().iter().zip(value) {
+                shader.set_shader_value(self.location[&(*kind, *name)], *value);
+            }
+
+            unsafe {
+                if i == pass_list.len() - 1 {
+                    let mut draw = draw.begin_shader_mode(shader);
+
+                    Self::blit(&mut draw, &*read, screen);
+                } else {
+                    let write = &mut *texture.add(i % 2);
+                    let size =
+                        Vector2::new(write.texture.width as f32, write.texture.height as f32);
+                    let mut draw = draw.begin_texture_mode(&context.thread, write);
+                    let mut draw = draw.begin_shader_mode(shader);
+
+                    Self::blit(&mut draw, &*read, size);
+
+                    read = write;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the uniform value list for a pass. None if the pass would have no effect.
+    fn get_value(&self, kind: PostKind, user: &User) -> Option<Vec<f32>> {
+        match kind {
+            PostKind::Screen => Some(vec![]),
+            PostKind::Tonemap => Some(vec![self.setting.exposure]),
+            PostKind::Grade => Some(vec![
+                user.video_brightness,
+                user.video_contrast,
+                user.video_gamma,
+            ]),
+            PostKind::Vignette => (self.setting.vignette > 0.0)
+                .then(|| vec![self.setting.vignette, self.setting.vignette_radius]),
+            PostKind::Aberration => {
+                let strength = self.setting.aberration + self.damage * Self::DAMAGE_ABERRATION;
+
+                (strength > 0.0).then(|| vec![strength])
+            }
+            PostKind::ColorBlind => (user.video_color_blind != ColorBlindKind::Off).then(|| {
+                vec![
+                    user.video_color_blind as i32 as f32,
+                    if user.video_color_blind_correct {
+                        1.0
+                    } else {
+                        0.0
+                    },
+                ]
+            }),
+        }
+    }
+
+    /// Draw a render texture, flipped, to fill a given size.
+    fn blit(draw: &mut impl RaylibDraw, texture: &RenderTexture2D, size: Vector2) {
+        draw.draw_texture_pro(
+            texture,
+            Rectangle::new(
+                0.0,
+                0.0,
+                texture.texture.width as f32,
+                -texture.texture.height as f32,
+            ),
+            Rectangle::new(0.0, 0.0, size.x, size.y),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,211 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::scene::*;
+
+//================================================================
+
+use raylib::prelude::*;
+
+//================================================================
+
+/// Screenshot and frame sequence recording, from the app's main loop.
+#[derive(Default)]
+pub struct Record {
+    /// Write a screenshot at the end of the current frame.
+    screenshot: bool,
+    /// Frame sequence being recorded, if any.
+    sequence: Option<Sequence>,
+}
+
+/// A frame sequence being recorded.
+struct Sequence {
+    /// Output folder.
+    path: String,
+    /// Index of the next frame.
+    frame: usize,
+}
+
+impl Record {
+    /// Path to the screenshot folder.
+    const PATH_SCREENSHOT: &str = "screenshot";
+    /// Path to the frame sequence folder.
+    const PATH_SEQUENCE: &str = "record";
+    /// Frame time while recording a frame sequence, regardless of the real frame time.
+    const FRAME_TIME: f32 = 1.0 / 60.0;
+    /// Frame time upper bound otherwise, to avoid a spiral after a long hitch.
+    const FRAME_TIME_MAX: f32 = 0.25;
+
+    /// Write a screenshot at the end of the current frame.
+    pub fn screenshot(&mut self) {
+        self.screenshot = true;
+    }
+
+    /// Begin recording a frame sequence, or end the current one. Returns the sequence's folder.
+    pub fn toggle_sequence(&mut self) -> anyhow::Result<String> {
+        if let Some(sequence) = self.sequence.take() {
+            return Ok(sequence.path);
+        }
+
+        let path = format!("{}/{}", Self::PATH_SEQUENCE, Self::get_time_stamp());
+
+        std::fs::create_dir_all(&path).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "Record::toggle_sequence(): Could not create folder \"{path}\": {error}"
+            ))
+        })?;
+
+        self.sequence = Some(Sequence {
+            path: path.clone(),
+            frame: 0,
+        });
+
+        Ok(path)
+    }
+
+    /// Check if a frame sequence is being recorded.
+    pub fn is_sequence(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Get the time to advance the simulation by for this frame. While recording a frame
+    /// sequence, this is fixed, so that every frame in the sequence is evenly spaced in game time
+    /// no matter how long each frame takes to draw and write.
+    pub fn get_frame_time(&self, handle: &RaylibHandle) -> f32 {
+        if self.sequence.is_some() {
+            Self::FRAME_TIME
+        } else {
+            handle.get_frame_time().min(Self::FRAME_TIME_MAX)
+        }
+    }
+
+    /// Write every pending capture. Must be called after every draw call for the frame, but
+    /// before the frame is presented. A frame sequence is stopped on the first frame that could
+    /// not be written.
+    pub fn draw(
+        &mut self,
+        draw: &mut RaylibDrawHandle,
+        thread: &RaylibThread,
+        scene: &Scene,
+    ) -> anyhow::Result<()> {
+        if !self.screenshot && self.sequence.is_none() {
+            return Ok(());
+        }
+
+        // flush any pending geometry so that it is part of the read-back.
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+        }
+
+        let frame = draw.load_image_from_screen(thread);
+        let mut result = Ok(());
+
+        if self.screenshot {
+            self.screenshot = false;
+
+            result = Self::write_screenshot(&frame, scene);
+        }
+
+        if let Some(sequence) = &mut self.sequence {
+            let path = format!("{}/{:06}.png", sequence.path, sequence.frame);
+
+            if let Err(error) = Self::export(&frame, &path) {
+                self.sequence = None;
+
+                return Err(error);
+            }
+
+            sequence.frame += 1;
+        }
+
+        result
+    }
+
+    /// Write a screenshot, for both the composited frame and the internal render texture.
+    fn write_screenshot(frame: &Image, scene: &Scene) -> anyhow::Result<()> {
+        let path = format!("{}/{}", Self::PATH_SCREENSHOT, Self::get_time_stamp());
+
+        std::fs::create_dir_all(Self::PATH_SCREENSHOT).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "Record::write_screenshot(): Could not create folder \"{}\": {error}",
+                Self::PATH_SCREENSHOT
+            ))
+        })?;
+
+        // composited frame, at window resolution, with post-process and interface.
+        Self::export(frame, &format!("{path}_frame.png"))?;
+
+        // internal render texture, at "video_scale" resolution.
+        if let Some(image) = scene.get_render_image() {
+            Self::export(&image, &format!("{path}_scene.png"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write an image to a file.
+    fn export(image: &Image, path: &str) -> anyhow::Result<()> {
+        if image.export_image(path) {
+            Ok(())
+        } else {
+            Err(anyhow::Error::msg(format!(
+                "Record::export(): Could not write image \"{path}\"."
+            )))
+        }
+    }
+
+    /// Get a time stamp for a file name, in milli-seconds since the UNIX epoch.
+    fn get_time_stamp() -> u128 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default()
+    }
+}
@@ -48,10 +48,21 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::animation::Pose;
 use crate::app::*;
 use crate::asset::*;
+use crate::camera::*;
+use crate::caption::*;
+use crate::debug::*;
+use crate::decal::*;
+use crate::environment::*;
 use crate::helper::*;
+use crate::particle::*;
 use crate::physical::*;
+use crate::post::*;
+use crate::shader::*;
+use crate::stat::*;
+use crate::user::ShadowQuality;
 
 //================================================================
 
@@ -61,13 +72,96 @@ use raylib::prelude::*;
 
 //================================================================
 
-/*
-hashmap where key is model path and value is transform list.
+/// Per-instance material override, for instanced drawing. Passed to the light shader through the
+/// "instanceTint" and "instanceEmissive" vertex attributes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InstanceOverride {
+    /// Multiplied with the material's base color.
+    pub tint: Color,
+    /// Added to the material's emissive color.
+    pub emissive: Color,
+    /// Multiplied with the material's alpha. An instance with any alpha under 1.0 is drawn in the
+    /// sorted transparent pass.
+    pub alpha: f32,
+}
 
-*/
+impl InstanceOverride {
+    /// Float count per instance in the attribute buffer.
+    const STRIDE: usize = 8;
+
+    /// Create a new override, with only an alpha multiplier.
+    pub fn new_alpha(alpha: f32) -> Self {
+        Self {
+            alpha,
+            ..Default::default()
+        }
+    }
+
+    /// Check if this override makes an instance transparent.
+    fn is_transparent(&self) -> bool {
+        self.alpha < 1.0 || self.tint.a < 255
+    }
+
+    /// Get the attribute data for this instance: tint (with alpha), then emissive.
+    fn data(&self) -> [f32; Self::STRIDE] {
+        let tint = self.tint.color_normalize();
+        let emissive = self.emissive.color_normalize();
+
+        [
+            tint.x,
+            tint.y,
+            tint.z,
+            tint.w * self.alpha,
+            emissive.x,
+            emissive.y,
+            emissive.z,
+            0.0,
+        ]
+    }
+}
+
+impl Default for InstanceOverride {
+    fn default() -> Self {
+        Self {
+            tint: Color::WHITE,
+            emissive: Color::BLACK,
+            alpha: 1.0,
+        }
+    }
+}
 
-struct Draw {
-    matrix: raylib::math::Matrix,
+/// Every instance of a model to draw this frame.
+#[derive(Default)]
+struct DrawBatch {
+    /// Opaque instance transform list.
+    matrix: Vec<raylib::math::Matrix>,
+    /// Opaque instance override list. Same length as "matrix".
+    instance: Vec<InstanceOverride>,
+    /// Transparent instance list, drawn in the sorted transparent pass.
+    blend: Vec<(raylib::math::Matrix, InstanceOverride)>,
+    /// Skinned instance list, each with its own pose. The pose is shared by every instance of a
+    /// model, so these are never instanced: the model is posed right before each instance's draw.
+    skin: Vec<(raylib::math::Matrix, InstanceOverride, Pose)>,
+}
+
+impl DrawBatch {
+    /// Add an instance to the opaque or transparent list, based on its override.
+    fn push(&mut self, matrix: raylib::math::Matrix, instance: InstanceOverride) {
+        if instance.is_transparent() {
+            self.blend.push((matrix, instance));
+        } else {
+            self.matrix.push(matrix);
+            self.instance.push(instance);
+        }
+    }
+
+    /// Remove every instance.
+    fn clear(&mut self) {
+        self.matrix.clear();
+        self.instance.clear();
+        self.blend.clear();
+        self.skin.clear();
+    }
 }
 
 /// Scene manager.
@@ -79,15 +173,34 @@ pub struct Scene<'a> {
     sound_list: Vec<Noise>,
     music_list: Vec<Noise>,
     light_list: Vec<Light>,
+    /// Index of the light uploaded to each light shader slot.
+    light_slot: Vec<Option<usize>>,
     room_list: Vec<Room>,
     // TO-DO make setter for this.
     view_list: Vec<View>,
     path_list: Vec<Path>,
-    draw_list: HashMap<String, Vec<raylib::math::Matrix>>,
-    particle_list: Vec<Particle>,
+    /// Every instance submitted this frame, by model path.
+    draw_list: HashMap<String, DrawBatch>,
+    /// Every instance left after culling, by level of detail model path. Built from "draw_list"
+    /// before the main pass.
+    draw_view: HashMap<String, DrawBatch>,
+    pub camera: CameraSystem,
+    pub caption: CaptionQueue,
+    pub particle: ParticleSystem,
+    pub decal: DecalSystem,
+    pub debug: DebugDraw,
+    pub shader_light: ShaderLight,
+    shadow: Shadow,
+    instance_buffer: InstanceBuffer,
+    pub post: Post,
+    pub environment: Environment,
     pub physical: Physical,
     pub room_rigid: Option<RigidBodyHandle>,
     pub pause: bool,
+    /// Time to advance every per-frame state (particle, decal, environment...) by.
+    pub frame_time: f32,
+    /// Draw counters for the last drawn frame.
+    pub draw_stat: DrawStat,
 }
 
 impl<'a> Scene<'a> {
@@ -97,14 +210,44 @@ impl<'a> Scene<'a> {
         point: Vector3,
         angle: (Vector3, f32),
         scale: Vector3,
+        instance: InstanceOverride,
     ) -> anyhow::Result<()> {
         let point = raylib::math::Matrix::translate(point.x, point.y, point.z);
         let scale = raylib::math::Matrix::scale(scale.x, scale.y, scale.z);
         let angle = raylib::math::Matrix::rotate(angle.0, angle.1.to_radians());
         let matrix = (scale * angle) * point;
 
-        let entry = self.draw_list.entry(path.to_string()).or_default();
-        entry.push(matrix);
+        self.draw_model_matrix(path, matrix, instance)
+    }
+
+    /// Draw a model with a given world transform, such as one given by a bone attachment.
+    pub fn draw_model_matrix(
+        &mut self,
+        path: &str,
+        matrix: raylib::math::Matrix,
+        instance: InstanceOverride,
+    ) -> anyhow::Result<()> {
+        self.draw_list
+            .entry(path.to_string())
+            .or_default()
+            .push(matrix, instance);
+
+        Ok(())
+    }
+
+    /// Draw a skinned model with a given world transform, in a given pose.
+    pub fn draw_model_pose(
+        &mut self,
+        path: &str,
+        matrix: raylib::math::Matrix,
+        instance: InstanceOverride,
+        pose: Pose,
+    ) -> anyhow::Result<()> {
+        self.draw_list
+            .entry(path.to_string())
+            .or_default()
+            .skin
+            .push((matrix, instance, pose));
 
         Ok(())
     }
@@ -116,31 +259,25 @@ impl<'a> Scene<'a> {
             (context.handle.get_screen_height() as f32 * app.user.video_scale) as u32,
         )?);
 
-        self.asset.set_shader(
-            context,
-            "screen",
-            Some("data/shader/base.vs"),
-            Some("data/shader/screen.fs"),
-        )?;
+        let texture = self.texture.as_ref().unwrap();
 
-        let light = self.asset.set_shader(
+        self.post.initialize(
+            &mut self.asset,
             context,
-            "light",
-            Some("data/shader/light.vs"),
-            Some("data/shader/light.fs"),
+            Vector2::new(texture.texture.width as f32, texture.texture.height as f32),
         )?;
 
-        light.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MVP as usize] =
-            light.get_shader_location("mvp");
-        light.locs_mut()[ShaderLocationIndex::SHADER_LOC_VECTOR_VIEW as usize] =
-            light.get_shader_location("viewPos");
-        light.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MODEL as usize] =
-            light.get_shader_location_attribute("instanceTransform");
+        let mut define = ShaderDefine::default();
 
-        light.set_shader_value(
-            light.get_shader_location("ambient"),
-            Vector4::new(0.5, 0.5, 0.5, 1.0),
-        );
+        if app.user.video_shadow != ShadowQuality::Off {
+            define = define
+                .set_flag("SHADOW", true)
+                .set("SHADOW_AXIS", Shadow::TILE_AXIS);
+        }
+
+        self.shader_light = ShaderLight::new(&mut self.asset, context, define)?;
+        self.shadow = Shadow::new(&mut self.asset, context, app.user.video_shadow)?;
+        self.light_slot = vec![None; self.shader_light.light_list.len()];
 
         self.camera_3d =
             Camera3D::perspective(Vector3::zero(), Vector3::zero(), Vector3::up(), 90.0);
@@ -156,7 +293,7 @@ impl<'a> Scene<'a> {
                 let direction_b = raylib::math::Ray::new(view.point, direction.x * -1.0);
 
                 let model = self.asset.get_model(&room.model)?;
-                let bound = model.model.get_model_bounding_box();
+                let bound = model.bound;
 
                 let hit_f = bound.get_ray_collision_box(direction_f);
                 let hit_b = bound.get_ray_collision_box(direction_b);
@@ -207,13 +344,19 @@ impl<'a> Scene<'a> {
     }
 
     pub fn set_model(&mut self, context: &mut Context, path: &str) -> anyhow::Result<()> {
-        let shader = self.asset.get_shader("light")? as *const Shader;
+        let shader = self.asset.get_shader(&self.shader_light.name)? as *const Shader;
         let model = self.asset.set_model(context, path)?;
 
         for material in model.model.materials_mut() {
             material.shader = unsafe { **shader };
         }
 
+        let level: Vec<String> = model.level.iter().map(|level| level.path.clone()).collect();
+
+        for level in level {
+            self.set_model(context, &level)?;
+        }
+
         Ok(())
     }
 
@@ -273,9 +416,48 @@ impl<'a> Scene<'a> {
             music.is_stream_playing()
         });
 
+        self.caption.update(&app.user, self.frame_time);
+
         Ok(())
     }
 
+    /// Get the visible and total room count, for the last drawn frame.
+    pub fn get_room_count(&self) -> (usize, usize) {
+        let visible = self.room_list.iter().filter(|room| room.visible).count();
+
+        (visible, self.room_list.len())
+    }
+
+    /// Get the active and total light count, for the last drawn frame. A light is active if it is
+    /// enabled and its room (if any) is visible.
+    pub fn get_light_count(&self) -> (usize, usize) {
+        let active = self
+            .light_list
+            .iter()
+            .filter(|light| {
+                light.enable && light.room.is_none_or(|room| self.room_list[room].visible)
+            })
+            .count();
+
+        (active, self.light_list.len())
+    }
+
+    /// Get the live sound and music count.
+    pub fn get_noise_count(&self) -> (usize, usize) {
+        (self.sound_list.len(), self.music_list.len())
+    }
+
+    /// Read the internal render texture back to an image, at "video_scale" resolution.
+    pub fn get_render_image(&self) -> Option<Image> {
+        let texture = self.texture.as_ref()?;
+        let mut image = unsafe { Image::from_raw(ffi::LoadImageFromTexture(texture.texture)) };
+
+        // render textures are stored bottom-up.
+        image.flip_vertical();
+
+        Some(image)
+    }
+
     pub fn update_resolution(&mut self, context: &mut Context, scale: f32) -> anyhow::Result<()> {
         let size = Vector2::new(
             context.handle.get_screen_width() as f32 * scale,
@@ -288,6 +470,8 @@ impl<'a> Scene<'a> {
             size.y as u32,
         )?);
 
+        self.post.update_resolution(context, size)?;
+
         Ok(())
     }
 
@@ -301,133 +485,531 @@ impl<'a> Scene<'a> {
         draw: &mut RaylibDrawHandle,
         mut call: F,
     ) -> anyhow::Result<()> {
-        let shader = self.asset.get_shader("light")?;
+        let room = Room::active_index(self, self.camera_3d.position);
+
+        self.environment.update(room, self.frame_time);
+
+        let shader = self.asset.get_shader(&self.shader_light.name)?;
         shader.set_shader_value(
             shader.locs()[ShaderLocationIndex::SHADER_LOC_VECTOR_VIEW as usize],
             self.camera_3d.position,
         );
 
+        self.environment.upload(shader, &self.shader_light);
+
         let scn = { self as *mut Self };
+        let screen = {
+            let texture = self.texture.as_ref().unwrap();
+
+            Rectangle::new(
+                0.0,
+                0.0,
+                texture.texture.width as f32,
+                texture.texture.height as f32,
+            )
+        };
+
+        for room in &mut self.room_list {
+            room.visit = false;
+            room.visible = false;
+        }
+
+        // draw every room if there is no portal to cull with, or if the camera is outside of every room.
+        unsafe {
+            match room {
+                Some(room) if !self.view_list.is_empty() => {
+                    Room::traverse(&mut *scn, room, screen)?;
+                }
+                _ => {
+                    for i in 0..self.room_list.len() {
+                        Room::draw(&mut *scn, i)?;
+                    }
+                }
+            }
+        }
+
+        self.draw_stat = DrawStat::default();
+
+        // shadow maps must be drawn before the main pass, as they use their own frame-buffer.
+        self.draw_shadow()?;
+
+        {
+            let room_list: Vec<(&str, BoundingBox)> = self
+                .room_list
+                .iter()
+                .map(|room| (room.model.as_str(), room.bound))
+                .collect();
+
+            self.decal
+                .update(&mut self.asset, context, &room_list, self.frame_time)?;
+        }
+
+        self.particle
+            .update(&mut self.asset, context, self.frame_time)?;
+
+        self.draw_cull()?;
+
         let texture = self.texture.as_mut().unwrap();
         let mut draw = draw.begin_texture_mode(&context.thread, texture);
         let mut draw = draw.begin_mode3D(self.camera_3d);
 
-        draw.clear_background(Color::BLACK);
+        draw.clear_background(self.environment.get_clear_color());
+
+        self.environment
+            .draw_sky(&mut draw, self.camera_3d.position);
 
-        if draw.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-            self.physical.draw();
+        let shader = self.asset.get_shader(&self.shader_light.name)? as *mut Shader;
+
+        if self.shadow.frame != 0 {
+            unsafe {
+                ffi::rlActiveTextureSlot(Shadow::TEXTURE_SLOT);
+                ffi::rlEnableTexture(self.shadow.depth);
+                ffi::rlActiveTextureSlot(0);
+            }
         }
 
-        for room in &mut self.room_list {
-            room.visit = false;
-            room.visible = false;
+        // opaque and masked meshes of every opaque instance are drawn first, instanced per model.
+        for (model, batch) in &self.draw_view {
+            if batch.matrix.is_empty() {
+                continue;
+            }
+
+            self.draw_stat.batch += 1;
+            self.draw_stat.instance += batch.matrix.len();
+
+            let bound = self.asset.get_model(model)?.bound;
+
+            unsafe {
+                Light::upload(&mut *scn, batch_bound(bound, &batch.matrix))?;
+            }
+
+            let model = self.asset.get_model(model)?;
+            let matrix = batch
+                .matrix
+                .iter()
+                .map(|f| f.into())
+                .collect::<Vec<ffi::Matrix>>();
+            let instance = batch
+                .instance
+                .iter()
+                .flat_map(InstanceOverride::data)
+                .collect::<Vec<f32>>();
+            let buffer = unsafe { (*scn).instance_buffer.update(&instance) };
+
+            for i in 0..model.model.meshes().len() {
+                if model.mesh_material(i).alpha == AlphaMode::Blend {
+                    continue;
+                }
+
+                unsafe {
+                    draw_mesh_instanced(
+                        &mut *shader,
+                        &self.shader_light,
+                        model,
+                        i,
+                        &matrix,
+                        buffer,
+                    );
+                }
+
+                self.draw_stat.call += 1;
+            }
         }
 
-        unsafe {
-            // HACK: menu view isn't working, i assume it's we don't have any view node?
-            if self.view_list.is_empty() {
-                for room in &self.room_list {
-                    let model = self.asset.get_model(&room.model).unwrap();
-                    draw.draw_model(&model.model, Vector3::zero(), 1.0, Color::WHITE);
+        // skinned instances are posed and drawn one at a time.
+        for (model, batch) in &self.draw_view {
+            for (matrix, instance, pose) in &batch.skin {
+                if instance.is_transparent() {
+                    continue;
+                }
+
+                self.draw_stat.batch += 1;
+                self.draw_stat.instance += 1;
+
+                let bound = self.asset.get_model(model)?.bound;
+
+                unsafe {
+                    Light::upload(&mut *scn, batch_bound(bound, &[*matrix]))?;
+                }
+
+                let model = self.asset.get_model(model)?;
+                let matrix = [matrix.into()];
+                let buffer = unsafe { (*scn).instance_buffer.update(&instance.data()) };
+
+                pose.apply(model)?;
+
+                for i in 0..model.model.meshes().len() {
+                    if model.mesh_material(i).alpha == AlphaMode::Blend {
+                        continue;
+                    }
+
+                    unsafe {
+                        draw_mesh_instanced(
+                            &mut *shader,
+                            &self.shader_light,
+                            model,
+                            i,
+                            &matrix,
+                            buffer,
+                        );
+                    }
+
+                    self.draw_stat.call += 1;
                 }
-            } else if let Some(room) = Room::active_index(&*scn, self.camera_3d.position) {
-                Room::traverse(&mut *scn, &mut draw, room, true);
             }
         }
 
-        for particle in &mut self.particle_list {
-            particle.draw_3d(&mut draw, &self.camera_3d, &mut self.asset)?;
+        // transparent instances, and blended meshes of every opaque instance, are drawn last, back
+        // to front, without writing depth. the second value is whether to draw every mesh in the
+        // model, or only the blended ones. the last value is the pose of a skinned instance.
+        let mut blend_list: Vec<(
+            f32,
+            bool,
+            &str,
+            raylib::math::Matrix,
+            InstanceOverride,
+            Option<&Pose>,
+        )> = Vec::default();
+
+        for (model, batch) in &self.draw_view {
+            let distance = |matrix: &raylib::math::Matrix| {
+                matrix_point(matrix).distance_to(self.camera_3d.position)
+            };
+            let has_blend = self.asset.get_model(model)?.has_blend();
+
+            if has_blend {
+                for (matrix, instance) in batch.matrix.iter().zip(&batch.instance) {
+                    blend_list.push((
+                        distance(matrix),
+                        false,
+                        model.as_str(),
+                        *matrix,
+                        *instance,
+                        None,
+                    ));
+                }
+            }
+
+            for (matrix, instance) in &batch.blend {
+                blend_list.push((
+                    distance(matrix),
+                    true,
+                    model.as_str(),
+                    *matrix,
+                    *instance,
+                    None,
+                ));
+            }
+
+            for (matrix, instance, pose) in &batch.skin {
+                let every = instance.is_transparent();
+
+                if every || has_blend {
+                    blend_list.push((
+                        distance(matrix),
+                        every,
+                        model.as_str(),
+                        *matrix,
+                        *instance,
+                        Some(pose),
+                    ));
+                }
+            }
         }
 
-        for (model, matrix) in &mut self.draw_list {
-            if !matrix.is_empty() {
+        blend_list.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        if !blend_list.is_empty() {
+            unsafe {
+                ffi::rlDrawRenderBatchActive();
+                ffi::rlDisableDepthMask();
+            }
+
+            for (_, every, model, matrix, instance, pose) in blend_list {
+                let bound = self.asset.get_model(model)?.bound;
+
+                unsafe {
+                    Light::upload(&mut *scn, batch_bound(bound, &[matrix]))?;
+                }
+
                 let model = self.asset.get_model(model)?;
+                let matrix = [matrix.into()];
+                let buffer = unsafe { (*scn).instance_buffer.update(&instance.data()) };
 
-                for (i, mesh) in model.model.meshes().iter().enumerate() {
-                    let matrix = matrix
-                        .iter()
-                        .map(|f| f.into())
-                        .collect::<Vec<ffi::Matrix>>();
+                if let Some(pose) = pose {
+                    pose.apply(model)?;
+                }
+
+                for i in 0..model.model.meshes().len() {
+                    if !every && model.mesh_material(i).alpha != AlphaMode::Blend {
+                        continue;
+                    }
 
                     unsafe {
-                        let material_mesh = *model.model.meshMaterial.wrapping_add(i);
-                        let material_data = &model.model.materials()[material_mesh as usize];
-
-                        ffi::DrawMeshInstanced(
-                            *mesh.as_ref(),
-                            **material_data,
-                            matrix.as_ptr(),
-                            matrix.len() as i32,
-                        )
+                        draw_mesh_instanced(
+                            &mut *shader,
+                            &self.shader_light,
+                            model,
+                            i,
+                            &matrix,
+                            buffer,
+                        );
                     }
+
+                    self.draw_stat.call += 1;
                 }
+            }
 
-                matrix.clear();
+            unsafe {
+                ffi::rlDrawRenderBatchActive();
+                ffi::rlEnableDepthMask();
             }
         }
 
+        for batch in self
+            .draw_list
+            .values_mut()
+            .chain(self.draw_view.values_mut())
+        {
+            batch.clear();
+        }
+
+        if self.shadow.frame != 0 {
+            unsafe {
+                ffi::rlActiveTextureSlot(Shadow::TEXTURE_SLOT);
+                ffi::rlDisableTexture();
+                ffi::rlActiveTextureSlot(0);
+            }
+        }
+
+        self.decal.draw(&mut draw, &self.asset)?;
+        self.particle
+            .draw(&mut draw, &self.camera_3d, &self.asset)?;
+
+        self.debug.draw_3d(&mut draw);
+
+        for light in &mut self.light_list {
+            light.dirty = false;
+        }
+
         call(&mut draw)
     }
 
+    /// Build the instance list for the main pass. Every instance outside of the camera frustum, or
+    /// past its model's fade distance, is culled. Every other instance is faded, and switched to
+    /// its model's level of detail for its camera distance. The shadow pass still draws every
+    /// submitted instance, as a caster outside of the frustum can shadow a visible surface.
+    fn draw_cull(&mut self) -> anyhow::Result<()> {
+        let aspect = match &self.texture {
+            Some(texture) => texture.texture.width as f32 / texture.texture.height.max(1) as f32,
+            None => 1.0,
+        };
+        let frustum = Frustum::new(self.camera_3d, aspect);
+
+        for (path, batch) in &self.draw_list {
+            let model = self.asset.get_model(path)?;
+            let instance_list = batch.matrix.iter().zip(&batch.instance).chain(
+                batch
+                    .blend
+                    .iter()
+                    .map(|(matrix, instance)| (matrix, instance)),
+            );
+
+            for (matrix, instance) in instance_list {
+                let distance = matrix_point(matrix).distance_to(self.camera_3d.position);
+                let fade = model.get_fade(distance);
+
+                if fade <= 0.0 || !frustum.has_box(transform_bound(model.bound, matrix)) {
+                    self.draw_stat.cull += 1;
+                    continue;
+                }
+
+                let instance = InstanceOverride {
+                    alpha: instance.alpha * fade,
+                    ..*instance
+                };
+                let path = model.get_level(distance).unwrap_or(path);
+
+                self.draw_view
+                    .entry(path.to_string())
+                    .or_default()
+                    .push(*matrix, instance);
+            }
+
+            // a level of detail model may not share the skeleton, so skinned instances keep the
+            // base model.
+            for (matrix, instance, pose) in &batch.skin {
+                let distance = matrix_point(matrix).distance_to(self.camera_3d.position);
+                let fade = model.get_fade(distance);
+
+                if fade <= 0.0 || !frustum.has_box(transform_bound(model.bound, matrix)) {
+                    self.draw_stat.cull += 1;
+                    continue;
+                }
+
+                let instance = InstanceOverride {
+                    alpha: instance.alpha * fade,
+                    ..*instance
+                };
+
+                self.draw_view
+                    .entry(path.to_string())
+                    .or_default()
+                    .skin
+                    .push((*matrix, instance, pose.clone()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw the shadow map of every shadowed light, closest to the camera first, until the shadow
+    /// budget runs out of atlas tiles. Each light is assigned its first tile in the shadow atlas.
+    fn draw_shadow(&mut self) -> anyhow::Result<()> {
+        if self.shadow.frame == 0 {
+            return Ok(());
+        }
+
+        let camera = self.camera_3d.position;
+
+        let mut list: Vec<(usize, f32)> = self
+            .light_list
+            .iter()
+            .enumerate()
+            .filter(|(_, light)| {
+                light.enable
+                    && light.shadow
+                    && light.room.is_none_or(|room| self.room_list[room].visible)
+            })
+            .map(|(i, light)| (i, light.point.distance_to(camera)))
+            .collect();
+
+        list.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut tile = vec![-1; self.light_list.len()];
+        let mut face = Vec::new();
+
+        for (i, _) in list {
+            let light_face = self.light_list[i].get_shadow_face(camera);
+
+            // a light that does not fit is skipped, so a closer point light does not starve every
+            // directional light behind it.
+            if face.len() + light_face.len() > self.shadow.budget {
+                continue;
+            }
+
+            tile[i] = face.len() as i32;
+            face.extend(light_face);
+        }
+
+        for (i, light) in self.light_list.iter_mut().enumerate() {
+            if light.shadow_tile != tile[i] {
+                light.shadow_tile = tile[i];
+                light.dirty = true;
+            }
+        }
+
+        let shader = self.asset.get_shader(&self.shader_light.name)?;
+
+        for (i, (view, projection)) in face.iter().enumerate() {
+            shader.set_shader_value_matrix(self.shader_light.shadow_matrix[i], *view * *projection);
+        }
+
+        shader.set_shader_value(self.shader_light.shadow_map, Shadow::TEXTURE_SLOT);
+        shader.set_shader_value(self.shader_light.shadow_filter, self.shadow.filter);
+        shader.set_shader_value(
+            self.shader_light.shadow_texel,
+            1.0 / self.shadow.size as f32,
+        );
+
+        let depth = **self.asset.get_shader(Shadow::SHADER_NAME)?;
+        let size = self.shadow.size / Shadow::TILE_AXIS as i32;
+
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlEnableFramebuffer(self.shadow.frame);
+            ffi::rlViewport(0, 0, self.shadow.size, self.shadow.size);
+            ffi::rlClearColor(255, 255, 255, 255);
+            ffi::rlClearScreenBuffers();
+            ffi::rlEnableDepthTest();
+
+            for (i, (view, projection)) in face.iter().enumerate() {
+                let x = (i % Shadow::TILE_AXIS) as i32 * size;
+                let y = (i / Shadow::TILE_AXIS) as i32 * size;
+
+                ffi::rlViewport(x, y, size, size);
+                ffi::rlSetMatrixProjection((*projection).into());
+                ffi::rlSetMatrixModelview((*view).into());
+
+                // transparent instances do not cast a shadow.
+                for (model, batch) in &self.draw_list {
+                    let model = self.asset.get_model(model)?;
+
+                    if !batch.matrix.is_empty() {
+                        let matrix = batch
+                            .matrix
+                            .iter()
+                            .map(|f| f.into())
+                            .collect::<Vec<ffi::Matrix>>();
+
+                        self.draw_stat.call += draw_shadow_caster(model, depth, &matrix);
+                    }
+
+                    for (matrix, instance, pose) in &batch.skin {
+                        if instance.is_transparent() {
+                            continue;
+                        }
+
+                        pose.apply(model)?;
+
+                        self.draw_stat.call += draw_shadow_caster(model, depth, &[matrix.into()]);
+                    }
+                }
+
+                ffi::rlDrawRenderBatchActive();
+            }
+
+            ffi::rlDisableFramebuffer();
+        }
+
+        Ok(())
+    }
+
+    /// Take every failure from the scene's systems since the last call, to report to the user.
+    pub fn take_failure(&mut self) -> Vec<String> {
+        let mut failure = self.particle.take_failure();
+
+        failure.extend(self.decal.take_failure());
+        failure.extend(self.caption.take_failure());
+
+        failure
+    }
+
     pub fn draw_2d<F: FnMut(&mut RaylibMode2D<'_, RaylibDrawHandle<'_>>) -> anyhow::Result<()>>(
         &mut self,
+        app: &App,
         context: &mut Context,
         draw: &mut RaylibDrawHandle,
         mut call: F,
     ) -> anyhow::Result<()> {
-        {
-            let texture = self.texture.as_mut().unwrap();
-
-            if draw.is_key_down(KeyboardKey::KEY_X) {
-                let mut draw = draw.begin_mode2D(self.camera_2d);
-
-                draw.draw_texture_pro(
-                    &texture,
-                    Rectangle::new(
-                        0.0,
-                        0.0,
-                        texture.texture.width as f32,
-                        -texture.texture.height as f32,
-                    ),
-                    Rectangle::new(
-                        0.0,
-                        0.0,
-                        context.handle.get_screen_width() as f32,
-                        context.handle.get_screen_height() as f32,
-                    ),
-                    Vector2::zero(),
-                    0.0,
-                    Color::WHITE,
-                );
-            } else {
-                let shader = self.asset.get_shader("screen")?;
-                let mut draw = draw.begin_shader_mode(shader);
-                let mut draw = draw.begin_mode2D(self.camera_2d);
-
-                draw.draw_texture_pro(
-                    &texture,
-                    Rectangle::new(
-                        0.0,
-                        0.0,
-                        texture.texture.width as f32,
-                        -texture.texture.height as f32,
-                    ),
-                    Rectangle::new(
-                        0.0,
-                        0.0,
-                        context.handle.get_screen_width() as f32,
-                        context.handle.get_screen_height() as f32,
-                    ),
-                    Vector2::zero(),
-                    0.0,
-                    Color::WHITE,
-                );
-            }
-        }
+        let texture = self.texture.as_ref().unwrap();
+
+        self.post.draw(
+            &mut self.asset,
+            context,
+            draw,
+            texture,
+            &app.user,
+            self.frame_time,
+        )?;
 
         let mut draw = draw.begin_mode2D(self.camera_2d);
 
-        call(&mut draw)
+        self.debug.draw_2d(&mut draw, self.camera_3d);
+
+        call(&mut draw)?;
+
+        app.view.draw_caption(&mut draw, &self.caption)
     }
 }
 
@@ -451,14 +1033,27 @@ impl<'a> Default for Scene<'a> {
             sound_list: Default::default(),
             music_list: Default::default(),
             light_list: Default::default(),
+            light_slot: Default::default(),
             room_list: Default::default(),
             view_list: Default::default(),
             path_list: Default::default(),
             draw_list: Default::default(),
-            particle_list: Default::default(),
+            draw_view: Default::default(),
+            camera: Default::default(),
+            caption: Default::default(),
+            particle: Default::default(),
+            decal: Default::default(),
+            debug: Default::default(),
+            shader_light: Default::default(),
+            shadow: Default::default(),
+            instance_buffer: Default::default(),
+            post: Default::default(),
+            environment: Default::default(),
             room_rigid: Default::default(),
             physical: Default::default(),
             pause: Default::default(),
+            frame_time: Default::default(),
+            draw_stat: Default::default(),
         }
     }
 }
@@ -486,14 +1081,14 @@ impl<'a> Room {
             scene.room_rigid = Some(scene.physical.new_rigid_fixed());
         }
 
-        let shader = scene.asset.get_shader("light")? as *const Shader;
+        let shader = scene.asset.get_shader(&scene.shader_light.name)? as *const Shader;
         let model = scene.asset.set_model(context, path)?;
 
         for material in model.model.materials_mut() {
             material.shader = unsafe { **shader };
         }
 
-        let bound = model.model.get_model_bounding_box();
+        let bound = model.bound;
 
         let collider = scene
             .physical
@@ -512,7 +1107,9 @@ impl<'a> Room {
             ),
         )?;
 
-        scene.physical.new_model(&model.model, scene.room_rigid)?;
+        scene
+            .physical
+            .new_model(&model.model, &model.mesh_surface(), scene.room_rigid)?;
 
         scene.room_list.push(Room {
             point: (bound.min + bound.max) * 0.5,
@@ -579,44 +1176,97 @@ impl<'a> Room {
         }
     }
 
-    // Recursively draw each room.
-    fn traverse(
-        scene: &mut Scene,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
-        room_index: usize,
-        inside: bool,
-    ) {
-        let scn = scene as *mut Scene;
-        let current_room = &mut scene.room_list[room_index];
+    // Draw each room visible through a screen-space clip rectangle, narrowing the rectangle
+    // through each portal. A room is only entered again if it can be seen through a wider
+    // rectangle than before, so every room is visited a bounded number of times.
+    fn traverse(scene: &mut Scene, room_index: usize, screen: Rectangle) -> anyhow::Result<()> {
+        let mut clip_list: Vec<Option<Rectangle>> = vec![None; scene.room_list.len()];
+        let mut queue = vec![(room_index, None, screen)];
 
-        if current_room.visit {
-            return;
-        }
+        clip_list[room_index] = Some(screen);
 
-        current_room.visit = true;
+        while let Some((room_index, from_view, clip)) = queue.pop() {
+            Self::draw(scene, room_index)?;
 
-        if current_room.is_visible(&scene.view_list) || inside {
-            current_room.visible = true;
+            let room_view = scene.room_list[room_index].view.clone();
 
-            (unsafe { &mut *scn }).draw_model(
-                &current_room.model,
-                Vector3::zero(),
-                (Vector3::zero(), 0.0),
-                Vector3::one(),
-            );
+            for index in room_view {
+                if Some(index) == from_view {
+                    continue;
+                }
 
-            //draw.draw_model(&model.model, Vector3::zero(), 1.0, Color::WHITE);
+                let view = &scene.view_list[index];
 
-            let c_r_view = current_room.view.clone();
+                if !view.visible {
+                    continue;
+                }
+
+                let Some(clip) = view
+                    .get_screen_shape(scene.camera_3d, screen)
+                    .get_collision_rec(&clip)
+                else {
+                    continue;
+                };
 
-            for view in &c_r_view {
-                for room in &(unsafe { &*scn }).view_list[*view].room {
-                    Self::traverse(scene, draw, *room, false);
+                for room in view.room.clone() {
+                    if room == room_index {
+                        continue;
+                    }
+
+                    let clip = match clip_list[room] {
+                        Some(other) if Self::clip_contain(&other, &clip) => continue,
+                        Some(other) => Self::clip_merge(&other, &clip),
+                        None => clip,
+                    };
+
+                    clip_list[room] = Some(clip);
+                    queue.push((room, Some(index), clip));
                 }
             }
-        } else {
-            current_room.visible = false;
         }
+
+        Ok(())
+    }
+
+    // Check if a clip rectangle fully contains another.
+    fn clip_contain(a: &Rectangle, b: &Rectangle) -> bool {
+        a.x <= b.x
+            && a.y <= b.y
+            && a.x + a.width >= b.x + b.width
+            && a.y + a.height >= b.y + b.height
+    }
+
+    // Get the smallest clip rectangle that contains two others.
+    fn clip_merge(a: &Rectangle, b: &Rectangle) -> Rectangle {
+        let min = Vector2::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Vector2::new(
+            (a.x + a.width).max(b.x + b.width),
+            (a.y + a.height).max(b.y + b.height),
+        );
+
+        Rectangle::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    // Draw a room, if it has not been drawn yet this frame.
+    fn draw(scene: &mut Scene, room_index: usize) -> anyhow::Result<()> {
+        let room = &mut scene.room_list[room_index];
+
+        if room.visit {
+            return Ok(());
+        }
+
+        room.visit = true;
+        room.visible = true;
+
+        let path = room.model.clone();
+
+        scene.draw_model(
+            &path,
+            Vector3::zero(),
+            (Vector3::zero(), 0.0),
+            Vector3::one(),
+            InstanceOverride::default(),
+        )
     }
 
     // Determine if the room is visible.
@@ -642,18 +1292,26 @@ impl<'a> Room {
 pub struct View {
     point: Vector3,
     angle: Vector3,
+    /// Half-extent of the portal quad, along the right (X) and up (Y) vector.
+    shape: Vector2,
     visible: bool,
     room: Vec<usize>,
 }
 
 impl View {
     /// Attach a new view node to the scene.
-    pub fn attach(scene: &mut Scene, point: Vector3, angle: Vector3) -> anyhow::Result<usize> {
+    pub fn attach(
+        scene: &mut Scene,
+        point: Vector3,
+        angle: Vector3,
+        shape: Vector2,
+    ) -> anyhow::Result<usize> {
         let index = scene.view_list.len();
 
         scene.view_list.push(View {
             point,
             angle,
+            shape,
             visible: Default::default(),
             room: Default::default(),
         });
@@ -661,6 +1319,41 @@ impl View {
         Ok(index)
     }
 
+    /// Get the screen-space rectangle of the portal quad. If any corner of the quad is behind the
+    /// camera, the whole screen is given instead, as the projection is not valid.
+    fn get_screen_shape(&self, camera: Camera3D, screen: Rectangle) -> Rectangle {
+        let direction = Direction::new_from_angle(&self.angle);
+        let forward = (camera.target - camera.position).normalized();
+        let x = direction.z * self.shape.x;
+        let y = direction.y * self.shape.y;
+
+        let mut min = Vector2::new(f32::MAX, f32::MAX);
+        let mut max = Vector2::new(f32::MIN, f32::MIN);
+
+        for corner in [x + y, x - y, -x + y, -x - y] {
+            let corner = self.point + corner;
+
+            if (corner - camera.position).dot(forward) <= 0.01 {
+                return screen;
+            }
+
+            let point: Vector2 = unsafe {
+                ffi::GetWorldToScreenEx(
+                    corner.into(),
+                    camera.into(),
+                    screen.width as i32,
+                    screen.height as i32,
+                )
+            }
+            .into();
+
+            min = Vector2::new(min.x.min(point.x), min.y.min(point.y));
+            max = Vector2::new(max.x.max(point.x), max.y.max(point.y));
+        }
+
+        Rectangle::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
     /// Set the visibility of this view portal.
     pub fn set_visible(scene: &mut Scene, index: usize, visible: bool) {
         let view = &mut scene.view_list[index];
@@ -692,6 +1385,8 @@ impl Noise {
     ) -> anyhow::Result<()> {
         let sound = scene.asset.get_sound(path)?;
 
+        scene.caption.push_sound(path);
+
         if sound.sound.is_playing() {
             for (i, alias) in sound.alias.iter().enumerate() {
                 if !alias.is_playing() {
@@ -743,6 +1438,8 @@ impl Noise {
         let music = scene.asset.get_music(path)?;
         music.play_stream();
 
+        scene.caption.push_sound(path);
+
         scene.music_list.push(Noise {
             point,
             range: 8.0,
@@ -769,24 +1466,28 @@ impl Path {
 
 //================================================================
 
-#[derive(Default)]
+/// A light source. Light data is kept on the CPU, and only the closest lights to each draw batch
+/// are uploaded to the light shader.
 pub struct Light {
-    /// Shader location for "enable".
-    enable: i32,
-    /// Shader location for "mode".
-    mode: i32,
-    /// Shader location for "point".
-    point: i32,
-    /// Shader location for "focus".
-    focus: i32,
-    /// Shader location for "color".
-    color: i32,
-    /// Shader location for "power".
-    power: i32,
-    /// Shader location for "range".
-    range: i32,
-    /// Shader location for "attenuation".
-    attenuation: i32,
+    enable: bool,
+    mode: LightMode,
+    point: Vector3,
+    focus: Vector3,
+    color: Vector4,
+    power: f32,
+    range: f32,
+    attenuation: f32,
+    /// Index of the room the light is in. None if the light is outside of every room, in which
+    /// case it is never culled by room visibility.
+    room: Option<usize>,
+    /// The room of the light must be found again, on the next physical tick.
+    locate: bool,
+    /// Cast shadows from this light.
+    shadow: bool,
+    /// Index of the first shadow atlas tile of the light. -1 if the light has no shadow this frame.
+    shadow_tile: i32,
+    /// Light data has changed since the last frame, and must be uploaded again.
+    dirty: bool,
 }
 
 impl Light {
@@ -800,101 +1501,250 @@ impl Light {
         range: f32,
         attenuation: f32,
     ) -> anyhow::Result<usize> {
-        let shader = scene.asset.get_shader("light")?;
-        let mut light = Light::default();
         let index = scene.light_list.len();
 
-        light.enable = shader.get_shader_location(&format!("light_list[{}].enable", index));
-        light.mode = shader.get_shader_location(&format!("light_list[{}].mode", index));
-        light.point = shader.get_shader_location(&format!("light_list[{}].point", index));
-        light.focus = shader.get_shader_location(&format!("light_list[{}].focus", index));
-        light.color = shader.get_shader_location(&format!("light_list[{}].color", index));
-        light.power = shader.get_shader_location(&format!("light_list[{}].power", index));
-        light.range = shader.get_shader_location(&format!("light_list[{}].range", index));
-        light.attenuation =
-            shader.get_shader_location(&format!("light_list[{}].attenuation", index));
-
-        scene.light_list.push(light);
-
-        Self::set_enable(scene, index, true)?;
-        Self::set_mode(scene, index, LightMode::Point)?;
-        Self::set_point(scene, index, point)?;
-        Self::set_focus(scene, index, focus)?;
+        scene.light_list.push(Light {
+            enable: true,
+            mode: LightMode::Point,
+            point,
+            focus,
+            color: Vector4::zero(),
+            power,
+            range,
+            attenuation,
+            room: None,
+            locate: true,
+            shadow: false,
+            shadow_tile: -1,
+            dirty: true,
+        });
+
         Self::set_color(scene, index, color)?;
-        Self::set_power(scene, index, power)?;
-        Self::set_range(scene, index, range)?;
-        Self::set_attenuation(scene, index, attenuation)?;
 
         Ok(index)
     }
 
+    /// Find the room of every light attached or moved since the last physical tick. Room sensors
+    /// can only be found once the physical simulation has been stepped, so this must be called
+    /// after each physical tick.
+    pub fn locate(scene: &mut Scene) {
+        for i in 0..scene.light_list.len() {
+            if scene.light_list[i].locate {
+                let room = Room::active_index(scene, scene.light_list[i].point);
+                let light = &mut scene.light_list[i];
+
+                light.room = room;
+                light.locate = false;
+            }
+        }
+    }
+
+    /// Upload the closest lights to a bounding box to the light shader. Lights in a room that is
+    /// not visible this frame, or out of range of the bounding box, are skipped. Lights keep their
+    /// slot from the last upload where possible, so only a change in the light set is uploaded.
+    fn upload(scene: &mut Scene, bound: BoundingBox) -> anyhow::Result<()> {
+        let mut list: Vec<(usize, f32)> = scene
+            .light_list
+            .iter()
+            .enumerate()
+            .filter(|(_, light)| {
+                light.enable && light.room.is_none_or(|room| scene.room_list[room].visible)
+            })
+            .map(|(i, light)| (i, light.distance(bound)))
+            .filter(|(i, distance)| *distance <= scene.light_list[*i].range)
+            .collect();
+
+        list.sort_by(|a, b| a.1.total_cmp(&b.1));
+        list.truncate(scene.light_slot.len());
+
+        let mut slot: Vec<Option<usize>> = scene
+            .light_slot
+            .iter()
+            .map(|light| light.filter(|light| list.iter().any(|(i, _)| i == light)))
+            .collect();
+
+        for (light, _) in list {
+            if !slot.contains(&Some(light))
+                && let Some(free) = slot.iter_mut().find(|slot| slot.is_none())
+            {
+                *free = Some(light);
+            }
+        }
+
+        let shader = scene.asset.get_shader(&scene.shader_light.name)?;
+
+        for (i, light) in slot.iter().enumerate() {
+            let handle = scene.shader_light.light_list[i];
+
+            match light {
+                Some(light) => {
+                    if scene.light_slot[i] != Some(*light) || scene.light_list[*light].dirty {
+                        let light = &scene.light_list[*light];
+
+                        shader.set_shader_value(handle.enable, 1);
+                        shader.set_shader_value(handle.mode, light.mode as i32);
+                        shader.set_shader_value(handle.point, light.point);
+                        shader.set_shader_value(handle.focus, light.focus);
+                        shader.set_shader_value(handle.color, light.color);
+                        shader.set_shader_value(handle.power, light.power);
+                        shader.set_shader_value(handle.range, light.range);
+                        shader.set_shader_value(handle.attenuation, light.attenuation);
+                        shader.set_shader_value(handle.shadow, light.shadow_tile);
+                    }
+                }
+                None => {
+                    if scene.light_slot[i].is_some() {
+                        shader.set_shader_value(handle.enable, 0);
+                    }
+                }
+            }
+        }
+
+        scene.light_slot = slot;
+
+        Ok(())
+    }
+
+    /// Get the view and projection matrix of every shadow map face of the light. Directional
+    /// lights have a single orthographic face centered on the camera, point lights have one
+    /// perspective face per cube side, in +X, -X, +Y, -Y, +Z, -Z order.
+    fn get_shadow_face(
+        &self,
+        camera: Vector3,
+    ) -> Vec<(raylib::math::Matrix, raylib::math::Matrix)> {
+        match self.mode {
+            LightMode::Directional => {
+                let range = Shadow::DIRECTIONAL_RANGE;
+                let mut direction = (self.focus - self.point).normalized();
+
+                if direction.length() == 0.0 {
+                    direction = Vector3::new(0.0, -1.0, 0.0);
+                }
+
+                let up = if direction.dot(Vector3::up()).abs() > 0.99 {
+                    Vector3::forward()
+                } else {
+                    Vector3::up()
+                };
+
+                vec![(
+                    raylib::math::Matrix::look_at(camera - direction * range * 2.0, camera, up),
+                    raylib::math::Matrix::ortho(-range, range, -range, range, 0.01, range * 4.0),
+                )]
+            }
+            LightMode::Point => {
+                let projection =
+                    raylib::math::Matrix::perspective(90.0_f32.to_radians(), 1.0, 0.05, self.range);
+                let face = [
+                    (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+                    (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+                    (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+                    (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+                    (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+                    (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+                ];
+
+                face.iter()
+                    .map(|(direction, up)| {
+                        (
+                            raylib::math::Matrix::look_at(self.point, self.point + *direction, *up),
+                            projection,
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Get the distance from the light to a bounding box. Directional lights are always in range.
+    fn distance(&self, bound: BoundingBox) -> f32 {
+        if let LightMode::Directional = self.mode {
+            return 0.0;
+        }
+
+        let point = Vector3::new(
+            self.point.x.clamp(bound.min.x, bound.max.x),
+            self.point.y.clamp(bound.min.y, bound.max.y),
+            self.point.z.clamp(bound.min.z, bound.max.z),
+        );
+
+        self.point.distance_to(point)
+    }
+
+    fn get_mutable<'a>(scene: &'a mut Scene, index: usize) -> anyhow::Result<&'a mut Light> {
+        let light = scene
+            .light_list
+            .get_mut(index)
+            .ok_or(anyhow::Error::msg(format!(
+                "Light::get_mutable(): Could not find light {index}."
+            )))?;
+
+        light.dirty = true;
+
+        Ok(light)
+    }
+
     /// Set the active state of the light.
     pub fn set_enable(scene: &mut Scene, index: usize, active: bool) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.enable, if active { 1 } else { 0 });
+        Self::get_mutable(scene, index)?.enable = active;
 
         Ok(())
     }
 
     /// Set the mode of the light.
     pub fn set_mode(scene: &mut Scene, index: usize, mode: LightMode) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.mode, mode as i32);
+        Self::get_mutable(scene, index)?.mode = mode;
 
         Ok(())
     }
 
-    /// Set the point of the light.
+    /// Set the point of the light. The light is assigned to the room it is now in, on the next
+    /// physical tick.
     pub fn set_point(scene: &mut Scene, index: usize, point: Vector3) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.point, point);
+        let light = Self::get_mutable(scene, index)?;
+
+        light.point = point;
+        light.locate = true;
 
         Ok(())
     }
 
     /// Set the focus of the light.
     pub fn set_focus(scene: &mut Scene, index: usize, focus: Vector3) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.focus, focus);
+        Self::get_mutable(scene, index)?.focus = focus;
 
         Ok(())
     }
 
     /// Set the color of the light.
     pub fn set_color(scene: &mut Scene, index: usize, color: Color) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-
-        let color = Vector4::new(
+        Self::get_mutable(scene, index)?.color = Vector4::new(
             (color.r as f32 / 255.0) * 0.1,
             (color.g as f32 / 255.0) * 0.1,
             (color.b as f32 / 255.0) * 0.1,
             (color.a as f32 / 255.0) * 0.1,
         );
 
-        shader.set_shader_value(handle.color, color);
-
         Ok(())
     }
 
     /// Set the power of the light.
     pub fn set_power(scene: &mut Scene, index: usize, power: f32) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.power, power);
+        Self::get_mutable(scene, index)?.power = power;
 
         Ok(())
     }
 
-    /// Set the range of the light.
+    /// Set the range of the light. The range is also the distance at which the light is culled.
     pub fn set_range(scene: &mut Scene, index: usize, range: f32) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.range, range);
+        Self::get_mutable(scene, index)?.range = range;
+
+        Ok(())
+    }
+
+    /// Set whether the light casts shadows. Shadowed lights are still subject to the shadow budget.
+    pub fn set_shadow(scene: &mut Scene, index: usize, shadow: bool) -> anyhow::Result<()> {
+        Self::get_mutable(scene, index)?.shadow = shadow;
 
         Ok(())
     }
@@ -905,14 +1755,222 @@ impl Light {
         index: usize,
         attenuation: f32,
     ) -> anyhow::Result<()> {
-        let handle = &scene.light_list[index];
-        let shader = scene.asset.get_shader("light")?;
-        shader.set_shader_value(handle.attenuation, attenuation);
+        Self::get_mutable(scene, index)?.attenuation = attenuation;
 
         Ok(())
     }
 }
 
+/// Get the translation of a transform.
+fn matrix_point(matrix: &raylib::math::Matrix) -> Vector3 {
+    Vector3::new(matrix.m12, matrix.m13, matrix.m14)
+}
+
+/// Draw a mesh of a model for every instance in a list. The per-instance override buffer (updated
+/// for the list beforehand) is bound to the mesh's vertex array alongside the transform buffer that
+/// "DrawMeshInstanced" sets up, and unbound after the draw, as the vertex array is shared with every
+/// other draw of the mesh.
+unsafe fn draw_mesh_instanced(
+    shader: &mut Shader,
+    uniform: &ShaderLight,
+    model: &AssetModel,
+    mesh: usize,
+    matrix: &[ffi::Matrix],
+    instance: u32,
+) {
+    let material = model.mesh_material(mesh);
+    let location: Vec<i32> = [uniform.instance_tint, uniform.instance_emissive]
+        .into_iter()
+        .filter(|location| *location >= 0)
+        .collect();
+
+    shader.set_shader_value(uniform.alpha_cutoff, material.alpha.cutoff());
+    shader.set_shader_value(uniform.material_map, material.map_flag());
+
+    unsafe {
+        let mesh_data = *model.model.meshes()[mesh].as_ref();
+        let material_mesh = *model.model.meshMaterial.wrapping_add(mesh);
+        let material_data = &model.model.materials()[material_mesh as usize];
+        let stride = (InstanceOverride::STRIDE * std::mem::size_of::<f32>()) as i32;
+
+        if material.double {
+            ffi::rlDisableBackfaceCulling();
+        }
+
+        ffi::rlEnableVertexArray(mesh_data.vaoId);
+        ffi::rlEnableVertexBuffer(instance);
+
+        for (i, location) in location.iter().enumerate() {
+            ffi::rlEnableVertexAttribute(*location as u32);
+            ffi::rlSetVertexAttribute(
+                *location as u32,
+                4,
+                ffi::RL_FLOAT as i32,
+                false,
+                stride,
+                (i * 4 * std::mem::size_of::<f32>()) as i32,
+            );
+            ffi::rlSetVertexAttributeDivisor(*location as u32, 1);
+        }
+
+        ffi::rlDisableVertexBuffer();
+        ffi::rlDisableVertexArray();
+
+        ffi::DrawMeshInstanced(
+            mesh_data,
+            **material_data,
+            matrix.as_ptr(),
+            matrix.len() as i32,
+        );
+
+        ffi::rlEnableVertexArray(mesh_data.vaoId);
+
+        for location in &location {
+            ffi::rlDisableVertexAttribute(*location as u32);
+        }
+
+        ffi::rlDisableVertexArray();
+
+        if material.double {
+            ffi::rlEnableBackfaceCulling();
+        }
+    }
+}
+
+/// A dynamic vertex buffer for per-instance data, shared by every instanced draw. Each batch
+/// updates the buffer in place, and the buffer is only re-allocated when a batch outgrows it.
+#[derive(Default)]
+struct InstanceBuffer {
+    /// Buffer handle. Zero if not allocated yet.
+    id: u32,
+    /// Buffer size, in bytes.
+    size: usize,
+}
+
+impl InstanceBuffer {
+    /// Upload the instance data of a batch, growing the buffer if needed. Returns the buffer
+    /// handle.
+    unsafe fn update(&mut self, data: &[f32]) -> u32 {
+        let size = std::mem::size_of_val(data);
+
+        unsafe {
+            if size > self.size {
+                if self.id != 0 {
+                    ffi::rlUnloadVertexBuffer(self.id);
+                }
+
+                // grow to the next power of two, so a slowly growing batch rarely re-allocates.
+                self.size = size.next_power_of_two();
+                self.id = ffi::rlLoadVertexBuffer(std::ptr::null(), self.size as i32, true);
+            }
+
+            ffi::rlUpdateVertexBuffer(
+                self.id,
+                data.as_ptr() as *const std::ffi::c_void,
+                size as i32,
+                0,
+            );
+        }
+
+        self.id
+    }
+}
+
+impl Drop for InstanceBuffer {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            unsafe {
+                ffi::rlUnloadVertexBuffer(self.id);
+            }
+        }
+    }
+}
+
+/// Draw every shadow casting mesh of a model, for every instance in a list, with the depth shader.
+/// Blended meshes do not cast a shadow. Returns the draw call count.
+unsafe fn draw_shadow_caster(
+    model: &AssetModel,
+    depth: ffi::Shader,
+    matrix: &[ffi::Matrix],
+) -> usize {
+    let mut call = 0;
+
+    for (i, mesh) in model.model.meshes().iter().enumerate() {
+        if model.mesh_material(i).alpha == AlphaMode::Blend {
+            continue;
+        }
+
+        unsafe {
+            let material_mesh = *model.model.meshMaterial.wrapping_add(i);
+            let mut material = *model.model.materials()[material_mesh as usize];
+            material.shader = depth;
+
+            ffi::DrawMeshInstanced(
+                *mesh.as_ref(),
+                material,
+                matrix.as_ptr(),
+                matrix.len() as i32,
+            );
+        }
+
+        call += 1;
+    }
+
+    call
+}
+
+/// Get the world-space bounding box of a model-space bounding box with a given transform.
+fn transform_bound(bound: BoundingBox, matrix: &raylib::math::Matrix) -> BoundingBox {
+    let mut world = BoundingBox::new(Vector3::one() * f32::MAX, Vector3::one() * f32::MIN);
+
+    for i in 0..8 {
+        let corner = Vector3::new(
+            if i & 1 == 0 { bound.min.x } else { bound.max.x },
+            if i & 2 == 0 { bound.min.y } else { bound.max.y },
+            if i & 4 == 0 { bound.min.z } else { bound.max.z },
+        )
+        .transform_with(*matrix);
+
+        world.min = Vector3::new(
+            world.min.x.min(corner.x),
+            world.min.y.min(corner.y),
+            world.min.z.min(corner.z),
+        );
+        world.max = Vector3::new(
+            world.max.x.max(corner.x),
+            world.max.y.max(corner.y),
+            world.max.z.max(corner.z),
+        );
+    }
+
+    world
+}
+
+/// Get the world-space bounding box of every instance in a draw batch. Only the translation of
+/// each instance is taken into account.
+fn batch_bound(bound: BoundingBox, matrix: &[raylib::math::Matrix]) -> BoundingBox {
+    let mut batch = BoundingBox::new(Vector3::one() * f32::MAX, Vector3::one() * f32::MIN);
+
+    for matrix in matrix {
+        let point = matrix_point(matrix);
+        let min = bound.min + point;
+        let max = bound.max + point;
+
+        batch.min = Vector3::new(
+            batch.min.x.min(min.x),
+            batch.min.y.min(min.y),
+            batch.min.z.min(min.z),
+        );
+        batch.max = Vector3::new(
+            batch.max.x.max(max.x),
+            batch.max.y.max(max.y),
+            batch.max.z.max(max.z),
+        );
+    }
+
+    batch
+}
+
 /// Every possible mode for a light.
 #[derive(Default, Copy, Clone)]
 pub enum LightMode {
@@ -923,47 +1981,103 @@ pub enum LightMode {
     Directional = 1,
 }
 
-//================================================================
-
-struct Particle {
-    point: Vector3,
-    path: String,
-    kind: ParticleKind,
-    node_list: Vec<ParticleNode>,
+/// Shadow atlas. Every shadowed light is given a set of square tiles in the atlas: one for a
+/// directional light, and six (one per cube side) for a point light.
+#[derive(Default)]
+struct Shadow {
+    /// Frame-buffer handle. Zero if shadows are disabled.
+    frame: u32,
+    /// Depth texture handle.
+    depth: u32,
+    /// Atlas size, in pixels.
+    size: i32,
+    /// Maximum atlas tile count used per frame.
+    budget: usize,
+    /// PCF filter radius, in texels.
+    filter: i32,
 }
 
-impl<'a> Particle {
-    pub fn attach(scene: &mut Scene, point: Vector3) -> anyhow::Result<()> {
-        scene.particle_list.push(Particle {
-            point,
-            path: "data/video/particle.png".to_string(),
-            kind: ParticleKind::Linear { point },
-            node_list: Vec::default(),
-        });
+impl Shadow {
+    /// Atlas tile count, per axis. Must match the "SHADOW_AXIS" define in the light shader.
+    const TILE_AXIS: usize = 4;
+    /// Atlas tile count. Must be the square of the tile count per axis.
+    const TILE_COUNT: usize = ShaderLight::SHADOW_COUNT;
+    /// Texture slot the atlas is bound to for the light shader. Material maps are bound by raylib
+    /// to the first twelve slots, so the atlas uses the last slot every GL 3.3 device must have.
+    const TEXTURE_SLOT: i32 = 15;
+    /// Half-extent of the directional shadow map, around the camera.
+    const DIRECTIONAL_RANGE: f32 = 16.0;
+    /// Asset name of the depth shader.
+    const SHADER_NAME: &str = "shadow";
+
+    /// Create the shadow atlas for a given quality. No atlas is created if shadows are disabled.
+    fn new(
+        asset: &mut Asset,
+        context: &mut Context,
+        quality: ShadowQuality,
+    ) -> anyhow::Result<Self> {
+        if quality == ShadowQuality::Off {
+            return Ok(Self::default());
+        }
 
-        Ok(())
-    }
+        let shader = asset.set_shader(
+            context,
+            Self::SHADER_NAME,
+            Some("data/shader/shadow.vs"),
+            Some("data/shader/shadow.fs"),
+        )?;
 
-    fn draw_3d(
-        &mut self,
-        draw: &mut RaylibMode3D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
-        camera: &Camera3D,
-        asset: &mut Asset<'a>,
-    ) -> anyhow::Result<()> {
-        let texture = asset.get_texture(&self.path)?;
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MVP as usize] =
+            shader.get_shader_location("mvp");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MODEL as usize] =
+            shader.get_shader_location_attribute("instanceTransform");
 
-        for particle in &mut self.node_list {
-            draw.draw_billboard(camera, texture, particle.point, 1.0, Color::WHITE);
-        }
+        let size = quality.size();
 
-        Ok(())
-    }
-}
+        unsafe {
+            let frame = ffi::rlLoadFramebuffer();
+            let depth = ffi::rlLoadTextureDepth(size, size, false);
+
+            ffi::rlEnableFramebuffer(frame);
+            ffi::rlFramebufferAttach(
+                frame,
+                depth,
+                ffi::rlFramebufferAttachType::RL_ATTACHMENT_DEPTH as i32,
+                ffi::rlFramebufferAttachTextureType::RL_ATTACHMENT_TEXTURE2D as i32,
+                0,
+            );
+
+            let complete = ffi::rlFramebufferComplete(frame);
+
+            ffi::rlDisableFramebuffer();
+
+            if !complete {
+                ffi::rlUnloadFramebuffer(frame);
+                ffi::rlUnloadTexture(depth);
+
+                return Err(anyhow::Error::msg(
+                    "Shadow::new(): Could not create shadow atlas frame-buffer.",
+                ));
+            }
 
-pub enum ParticleKind {
-    Linear { point: Vector3 },
+            Ok(Self {
+                frame,
+                depth,
+                size,
+                budget: quality.budget().min(Self::TILE_COUNT),
+                filter: quality.filter(),
+            })
+        }
+    }
 }
 
-struct ParticleNode {
-    point: Vector3,
+impl Drop for Shadow {
+    fn drop(&mut self) {
+        if self.frame != 0 {
+            unsafe {
+                ffi::rlUnloadFramebuffer(self.frame);
+                ffi::rlUnloadTexture(self.depth);
+            }
+        }
+    }
 }
@@ -0,0 +1,353 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::app::*;
+
+//================================================================
+
+use hashbrown::HashSet;
+use raylib::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+//================================================================
+
+/// A set of compile-time defines for a shader permutation.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderDefine {
+    /// Define list, sorted by name so that the key for a given set is always the same.
+    list: BTreeMap<String, String>,
+}
+
+impl ShaderDefine {
+    /// Set a define with a value, such as "#define LIGHT_COUNT 32".
+    pub fn set<T: Display>(mut self, name: &str, value: T) -> Self {
+        self.list.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Set a define with no value, such as "#define FOG". Will only be set if "value" is true.
+    pub fn set_flag(mut self, name: &str, value: bool) -> Self {
+        if value {
+            self.list.insert(name.to_string(), String::default());
+        }
+
+        self
+    }
+
+    /// Check if a define has been set.
+    pub fn has(&self, name: &str) -> bool {
+        self.list.contains_key(name)
+    }
+
+    /// Get the permutation key for this define set.
+    pub fn key(&self) -> String {
+        self.list
+            .iter()
+            .map(|(name, value)| {
+                if value.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{name}={value}")
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(";")
+    }
+
+    /// Get the permutation name for a given shader name, i.e. "light[FOG;LIGHT_COUNT=32]".
+    pub fn name(&self, name: &str) -> String {
+        if self.list.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name}[{}]", self.key())
+        }
+    }
+
+    /// Get the define list as GLSL source.
+    fn source(&self) -> String {
+        self.list
+            .iter()
+            .map(|(name, value)| format!("#define {name} {value}\n"))
+            .collect()
+    }
+}
+
+//================================================================
+
+/// Shader source pre-processor.
+pub struct ShaderSource;
+
+impl ShaderSource {
+    const INCLUDE: &str = "#include";
+    const VERSION: &str = "#version";
+
+    /// Load a shader source file, resolving every "#include" directive and injecting every define.
+    pub fn new(path: &str, define: &ShaderDefine) -> anyhow::Result<String> {
+        let mut source = String::new();
+        let mut stack = Vec::default();
+        let mut visit = HashSet::default();
+
+        Self::include(Path::new(path), &mut stack, &mut visit, &mut source)?;
+
+        // Defines must come after the "#version" directive, if any, which may come after a comment.
+        let mut index = 0;
+
+        for line in source.split_inclusive('\n') {
+            if Self::directive(line, Self::VERSION).is_some() {
+                let (head, body) = source.split_at(index + line.len());
+                return Ok(format!("{head}{}{body}", define.source()));
+            }
+
+            index += line.len();
+        }
+
+        Ok(format!("{}{source}", define.source()))
+    }
+
+    /// Get the argument of a pre-processor directive, if the line is that directive. The name must
+    /// be a whole token, so "#include_file" is not an "#include" directive.
+    fn directive<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+        let argument = line.trim_start().strip_prefix(name)?;
+
+        if argument.is_empty() || argument.starts_with(char::is_whitespace) {
+            Some(argument)
+        } else {
+            None
+        }
+    }
+
+    /// Recursively write a source file into the output buffer. Each file will only be included once.
+    fn include(
+        path: &Path,
+        stack: &mut Vec<PathBuf>,
+        visit: &mut HashSet<PathBuf>,
+        output: &mut String,
+    ) -> anyhow::Result<()> {
+        let path = path.to_path_buf();
+
+        if stack.contains(&path) {
+            return Err(anyhow::Error::msg(format!(
+                "ShaderSource::include(): Circular include for file \"{}\".",
+                path.display()
+            )));
+        }
+
+        if !visit.insert(path.clone()) {
+            return Ok(());
+        }
+
+        let file = std::fs::read_to_string(&path).map_err(|error| {
+            anyhow::Error::msg(format!(
+                "ShaderSource::include(): Could not read file \"{}\": {error}",
+                path.display()
+            ))
+        })?;
+        let folder = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        stack.push(path);
+
+        for line in file.lines() {
+            if let Some(include) = Self::directive(line, Self::INCLUDE) {
+                let include = include.trim().trim_matches('"');
+                Self::include(&folder.join(include), stack, visit, output)?;
+            } else {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+
+        stack.pop();
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// Uniform location data for a light slot in the "light" shader.
+#[derive(Default, Copy, Clone)]
+pub struct LightUniform {
+    /// Shader location for "enable".
+    pub enable: i32,
+    /// Shader location for "mode".
+    pub mode: i32,
+    /// Shader location for "point".
+    pub point: i32,
+    /// Shader location for "focus".
+    pub focus: i32,
+    /// Shader location for "color".
+    pub color: i32,
+    /// Shader location for "power".
+    pub power: i32,
+    /// Shader location for "range".
+    pub range: i32,
+    /// Shader location for "attenuation".
+    pub attenuation: i32,
+    /// Shader location for "shadow", the index of the light's first shadow atlas tile.
+    pub shadow: i32,
+}
+
+impl LightUniform {
+    fn new(shader: &Shader, index: usize) -> Self {
+        let location =
+            |name: &str| shader.get_shader_location(&format!("light_list[{index}].{name}"));
+
+        Self {
+            enable: location("enable"),
+            mode: location("mode"),
+            point: location("point"),
+            focus: location("focus"),
+            color: location("color"),
+            power: location("power"),
+            range: location("range"),
+            attenuation: location("attenuation"),
+            shadow: location("shadow"),
+        }
+    }
+}
+
+/// Uniform location data for the "light" shader.
+#[derive(Default)]
+pub struct ShaderLight {
+    /// Asset name of the active permutation.
+    pub name: String,
+    /// Shader location for "ambient".
+    pub ambient: i32,
+    /// Shader location for "fog_mode".
+    pub fog_mode: i32,
+    /// Shader location for "fog_color".
+    pub fog_color: i32,
+    /// Shader location for "fog_amount".
+    pub fog_amount: i32,
+    /// Shader location for "fog_density".
+    pub fog_density: i32,
+    /// Shader location for "fog_range", the linear fog start and end distance.
+    pub fog_range: i32,
+    /// Shader location for "alpha_cutoff".
+    pub alpha_cutoff: i32,
+    /// Attribute location for "instanceTint", the per-instance base color and alpha multiplier.
+    pub instance_tint: i32,
+    /// Attribute location for "instanceEmissive", the per-instance emissive color addition.
+    pub instance_emissive: i32,
+    /// Shader location for "material_map".
+    pub material_map: i32,
+    /// Shader location for "shadow_map".
+    pub shadow_map: i32,
+    /// Shader location for "shadow_filter".
+    pub shadow_filter: i32,
+    /// Shader location for "shadow_texel".
+    pub shadow_texel: i32,
+    /// Shader location for each entry in "shadow_matrix".
+    pub shadow_matrix: Vec<i32>,
+    /// Light slot list. Length is the same as the "LIGHT_COUNT" define.
+    pub light_list: Vec<LightUniform>,
+}
+
+impl ShaderLight {
+    /// Maximum light count for the light shader.
+    pub const LIGHT_COUNT: usize = 32;
+    /// Shadow atlas tile count for the light shader.
+    pub const SHADOW_COUNT: usize = 16;
+
+    /// Load the light shader permutation for a given define set, and resolve every uniform location.
+    pub fn new(
+        asset: &mut crate::asset::Asset,
+        context: &mut Context,
+        define: ShaderDefine,
+    ) -> anyhow::Result<Self> {
+        let define = define.set("LIGHT_COUNT", Self::LIGHT_COUNT);
+        let name = define.name("light");
+        let shader = asset.set_shader_define(
+            context,
+            "light",
+            Some("data/shader/light.vs"),
+            Some("data/shader/light.fs"),
+            &define,
+        )?;
+
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MVP as usize] =
+            shader.get_shader_location("mvp");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_VECTOR_VIEW as usize] =
+            shader.get_shader_location("viewPos");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MATRIX_MODEL as usize] =
+            shader.get_shader_location_attribute("instanceTransform");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MAP_NORMAL as usize] =
+            shader.get_shader_location("texture_normal");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MAP_EMISSION as usize] =
+            shader.get_shader_location("texture_emissive");
+        shader.locs_mut()[ShaderLocationIndex::SHADER_LOC_MAP_ROUGHNESS as usize] =
+            shader.get_shader_location("texture_roughness");
+
+        Ok(Self {
+            name,
+            ambient: shader.get_shader_location("ambient"),
+            fog_mode: shader.get_shader_location("fog_mode"),
+            fog_color: shader.get_shader_location("fog_color"),
+            fog_amount: shader.get_shader_location("fog_amount"),
+            fog_density: shader.get_shader_location("fog_density"),
+            fog_range: shader.get_shader_location("fog_range"),
+            alpha_cutoff: shader.get_shader_location("alpha_cutoff"),
+            instance_tint: shader.get_shader_location_attribute("instanceTint"),
+            instance_emissive: shader.get_shader_location_attribute("instanceEmissive"),
+            material_map: shader.get_shader_location("material_map"),
+            shadow_map: shader.get_shader_location("shadow_map"),
+            shadow_filter: shader.get_shader_location("shadow_filter"),
+            shadow_texel: shader.get_shader_location("shadow_texel"),
+            shadow_matrix: (0..Self::SHADOW_COUNT)
+                .map(|index| shader.get_shader_location(&format!("shadow_matrix[{index}]")))
+                .collect(),
+            light_list: (0..Self::LIGHT_COUNT)
+                .map(|index| LightUniform::new(shader, index))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,307 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+
+use crate::world::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+//================================================================
+
+/// A frame time category. Times are measured on the CPU side only; GPU work that is deferred by
+/// the driver is only visible as part of the total frame time.
+#[derive(Debug, Copy, Clone)]
+pub enum StatTime {
+    /// Entity tick, for every fixed step in the frame.
+    Tick,
+    /// Physical simulation tick, for every fixed step in the frame.
+    Physical,
+    /// World draw (3D), including shadow maps.
+    Draw3D,
+    /// World draw (2D), including post-process.
+    Draw2D,
+    /// User interface and console.
+    Interface,
+}
+
+impl StatTime {
+    /// Every category.
+    pub const LIST: [Self; 5] = [
+        Self::Tick,
+        Self::Physical,
+        Self::Draw3D,
+        Self::Draw2D,
+        Self::Interface,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Tick => "tick",
+            Self::Physical => "physical",
+            Self::Draw3D => "draw 3D",
+            Self::Draw2D => "draw 2D",
+            Self::Interface => "interface",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Tick => Color::SKYBLUE,
+            Self::Physical => Color::GREEN,
+            Self::Draw3D => Color::ORANGE,
+            Self::Draw2D => Color::VIOLET,
+            Self::Interface => Color::YELLOW,
+        }
+    }
+}
+
+//================================================================
+
+/// Draw counters for a single frame, filled in by the scene.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DrawStat {
+    /// Draw call count, for every pass (shadow maps included).
+    pub call: usize,
+    /// Instanced batch count (one per model path with at least one instance), main pass only.
+    pub batch: usize,
+    /// Instance count, main pass only.
+    pub instance: usize,
+    /// Culled instance count, by frustum or distance fade.
+    pub cull: usize,
+}
+
+/// Every statistic for a single frame.
+#[derive(Default, Clone)]
+struct StatFrame {
+    /// Time spent for each category, in seconds.
+    time: [f32; StatTime::LIST.len()],
+    /// Total frame time, in seconds.
+    frame: f32,
+    draw: DrawStat,
+    /// Visible and total room count.
+    room: (usize, usize),
+    /// Active and total light count.
+    light: (usize, usize),
+    /// Live sound and music count.
+    noise: (usize, usize),
+    entity: usize,
+    /// Rigid body and collider count.
+    body: (usize, usize),
+}
+
+/// Render statistics, with a frame time history for the overlay graph.
+#[derive(Default)]
+pub struct RenderStat {
+    /// Statistics for the frame currently being measured.
+    frame: StatFrame,
+    /// Statistics for every past frame, up to "HISTORY_TIME", oldest first.
+    history: VecDeque<StatFrame>,
+}
+
+impl RenderStat {
+    /// Length of the frame time history, in seconds.
+    const HISTORY_TIME: f32 = 4.0;
+    /// Frame time budget to draw as a reference line on the graph, in seconds.
+    const GRAPH_BUDGET: f32 = 1.0 / 60.0;
+    /// Graph height for the frame time budget, in pixels.
+    const GRAPH_SCALE: f32 = 64.0;
+    /// Graph width for the full history, in pixels.
+    const GRAPH_WIDTH: f32 = 320.0;
+    const TEXT_SCALE: i32 = 20;
+    const TEXT_POINT: Vector2 = Vector2::new(8.0, 48.0);
+
+    /// Add the time elapsed since a given instant to a category, for the current frame.
+    pub fn add_time(&mut self, kind: StatTime, since: Instant) {
+        self.frame.time[kind as usize] += since.elapsed().as_secs_f32();
+    }
+
+    /// End the current frame: gather every counter from the active world, if any, and push the
+    /// frame into the history.
+    pub fn end(&mut self, world: Option<&World>, frame_time: f32) {
+        let mut frame = std::mem::take(&mut self.frame);
+
+        frame.frame = frame_time;
+
+        if let Some(world) = world {
+            frame.draw = world.scene.draw_stat;
+            frame.room = world.scene.get_room_count();
+            frame.light = world.scene.get_light_count();
+            frame.noise = world.scene.get_noise_count();
+            frame.entity = world.entity_list.len();
+            frame.body = world.scene.physical.get_body_count();
+        }
+
+        self.history.push_back(frame);
+
+        let mut total: f32 = self.history.iter().map(|frame| frame.frame).sum();
+
+        while total > Self::HISTORY_TIME
+            && let Some(frame) = self.history.pop_front()
+        {
+            total -= frame.frame;
+        }
+    }
+
+    /// Draw the statistic overlay, for the last complete frame.
+    pub fn draw(&self, draw: &mut RaylibDrawHandle) {
+        let Some(frame) = self.history.back() else {
+            return;
+        };
+
+        let mut point = Self::TEXT_POINT;
+        let mut line = |draw: &mut RaylibDrawHandle, text: &str, color: Color| {
+            draw.draw_text(
+                text,
+                point.x as i32,
+                point.y as i32,
+                Self::TEXT_SCALE,
+                color,
+            );
+            point.y += Self::TEXT_SCALE as f32;
+        };
+
+        line(
+            draw,
+            &format!("frame: {:.2} ms", frame.frame * 1000.0),
+            Color::WHITE,
+        );
+
+        for kind in StatTime::LIST {
+            line(
+                draw,
+                &format!(
+                    "{}: {:.2} ms",
+                    kind.name(),
+                    frame.time[kind as usize] * 1000.0
+                ),
+                kind.color(),
+            );
+        }
+
+        line(
+            draw,
+            &format!(
+                "draw call: {} | batch: {} | instance: {} | cull: {}",
+                frame.draw.call, frame.draw.batch, frame.draw.instance, frame.draw.cull
+            ),
+            Color::WHITE,
+        );
+        line(
+            draw,
+            &format!("room: {}/{}", frame.room.0, frame.room.1),
+            Color::WHITE,
+        );
+        line(
+            draw,
+            &format!("light: {}/{}", frame.light.0, frame.light.1),
+            Color::WHITE,
+        );
+        line(
+            draw,
+            &format!("sound: {} | music: {}", frame.noise.0, frame.noise.1),
+            Color::WHITE,
+        );
+        line(
+            draw,
+            &format!(
+                "entity: {} | body: {} | collider: {}",
+                frame.entity, frame.body.0, frame.body.1
+            ),
+            Color::WHITE,
+        );
+
+        //================================================================
+
+        // frame time graph. each frame is a bar as wide as its frame time, stacked by category.
+        let base = Vector2::new(
+            point.x,
+            point.y + Self::GRAPH_SCALE * 2.0 + Self::TEXT_SCALE as f32,
+        );
+        let width = Self::GRAPH_WIDTH / Self::HISTORY_TIME;
+        let height = Self::GRAPH_SCALE / Self::GRAPH_BUDGET;
+
+        draw.draw_rectangle_v(
+            Vector2::new(base.x, base.y - Self::GRAPH_SCALE * 2.0),
+            Vector2::new(Self::GRAPH_WIDTH, Self::GRAPH_SCALE * 2.0),
+            Color::new(0, 0, 0, 127),
+        );
+
+        let mut x = base.x;
+
+        for frame in &self.history {
+            let w = (frame.frame * width).max(1.0);
+            let mut y = base.y;
+
+            for kind in StatTime::LIST {
+                let h = frame.time[kind as usize] * height;
+
+                draw.draw_rectangle_v(Vector2::new(x, y - h), Vector2::new(w, h), kind.color());
+
+                y -= h;
+            }
+
+            // remaining time, not accounted for by any category (driver, present, wait...).
+            let h = (frame.frame * height - (base.y - y)).max(0.0);
+
+            draw.draw_rectangle_v(Vector2::new(x, y - h), Vector2::new(w, h), Color::GRAY);
+
+            x += w;
+        }
+
+        draw.draw_line_v(
+            Vector2::new(base.x, base.y - Self::GRAPH_SCALE),
+            Vector2::new(base.x + Self::GRAPH_WIDTH, base.y - Self::GRAPH_SCALE),
+            Color::RED,
+        );
+    }
+}
@@ -0,0 +1,458 @@
+/*
+* Copyright (c) 2025 luxreduxdelux
+*
+* Redistribution and use in source and binary forms, with or without
+* modification, are permitted provided that the following conditions are met:
+*
+* 1. Redistributions of source code must retain the above copyright notice,
+* this list of conditions and the following disclaimer.
+*
+* 2. Redistributions in binary form must reproduce the above copyright notice,
+* this list of conditions and the following disclaimer in the documentation
+* and/or other materials provided with the distribution.
+*
+* Subject to the terms and conditions of this license, each copyright holder
+* and contributor hereby grants to those receiving rights under this license
+* a perpetual, worldwide, non-exclusive, no-charge, royalty-free, irrevocable
+* (except for failure to satisfy the conditions of this license) patent license
+* to make, have made, use, offer to sell, sell, import, and otherwise transfer
+* this software, where such license applies only to those patent claims, already
+* acquired or hereafter acquired, licensable by such copyright holder or
+* contributor that are necessarily infringed by:
+*
+* (a) their Contribution(s) (the licensed copyrights of copyright holders and
+* non-copyrightable additions of contributors, in source or binary form) alone;
+* or
+*
+* (b) combination of their Contribution(s) with the work of authorship to which
+* such Contribution(s) was added by such copyright holder or contributor, if,
+* at the time the Contribution is added, such addition causes such combination
+* to be necessarily infringed. The patent license shall not apply to any other
+* combinations which include the Contribution.
+*
+* Except as expressly stated above, no rights or licenses from any copyright
+* holder or contributor is granted under this license, whether expressly, by
+* implication, estoppel or otherwise.
+*
+* DISCLAIMER
+*
+* THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+* AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+* IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+* DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDERS OR CONTRIBUTORS BE LIABLE
+* FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+* DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+* SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+* CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+* OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+* OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+*/
+use crate::app::*;
+use crate::camera::*;
+use crate::caption::*;
+use crate::helper::*;
+use crate::scene::*;
+use crate::world::*;
+
+//================================================================
+
+use raylib::prelude::*;
+use serde::Deserialize;
+
+//================================================================
+
+/// A full-screen fade to or from black. Every world starts with a fade from black.
+#[derive(Debug, Copy, Clone)]
+pub struct Fade {
+    /// Opacity at the start of the fade, from 0.0 (clear) to 1.0 (black).
+    from: f32,
+    /// Opacity at the end of the fade.
+    to: f32,
+    /// Length of the fade, in seconds.
+    length: f32,
+    /// Time since the start of the fade, in seconds.
+    time: f32,
+}
+
+impl Fade {
+    pub fn new(from: f32, to: f32, length: f32) -> Self {
+        Self {
+            from,
+            to,
+            length,
+            time: 0.0,
+        }
+    }
+
+    /// Advance the fade.
+    pub fn tick(&mut self, time: f32) {
+        self.time = (self.time + time).min(self.length);
+    }
+
+    /// Get the current opacity.
+    pub fn get_alpha(&self) -> f32 {
+        if self.length <= 0.0 {
+            return self.to;
+        }
+
+        interpolate(
+            self.from,
+            self.to,
+            ease_in_out_cubic(self.time / self.length),
+        )
+    }
+
+    /// Draw the fade over the whole screen.
+    pub fn draw(&self, draw: &mut RaylibMode2D<'_, RaylibDrawHandle<'_>>) {
+        let alpha = self.get_alpha();
+
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let full = Vector2::new(
+            draw.get_render_width() as f32,
+            draw.get_render_height() as f32,
+        );
+
+        draw.draw_rectangle_v(
+            Vector2::zero(),
+            full,
+            Color::new(0, 0, 0, 0).lerp(Color::BLACK, alpha),
+        );
+    }
+}
+
+impl Default for Fade {
+    fn default() -> Self {
+        Self::new(1.0, 0.0, 1.0)
+    }
+}
+
+//================================================================
+
+/// Camera track key: play a camera path, from "data/camera".
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineCamera {
+    pub time: f32,
+    /// Camera path name.
+    pub path: String,
+    /// Blend length from the previous camera, in seconds.
+    #[serde(default = "TimelineCamera::blend_default")]
+    pub blend: f32,
+}
+
+impl TimelineCamera {
+    fn blend_default() -> f32 {
+        CameraSystem::BLEND
+    }
+}
+
+/// Entity input track key: send an input to an entity, by index in the level's entity list.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineInput {
+    pub time: f32,
+    pub entity: usize,
+    pub input: String,
+}
+
+/// Sound track key: play a sound or a music.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineSound {
+    pub time: f32,
+    /// Path to the sound or music file.
+    pub path: String,
+    /// Point in the world. None if the sound should not spatialize.
+    #[serde(default)]
+    pub point: Option<Vector3>,
+    /// Stream the file as a music, rather than a sound.
+    #[serde(default)]
+    pub music: bool,
+}
+
+/// Subtitle track key: push a caption to the caption queue.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineSubtitle {
+    pub time: f32,
+    #[serde(flatten)]
+    pub caption: Caption,
+}
+
+/// Fade track key: fade the screen to or from black.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineFade {
+    pub time: f32,
+    /// Opacity at the start of the fade, from 0.0 (clear) to 1.0 (black).
+    pub from: f32,
+    /// Opacity at the end of the fade.
+    pub to: f32,
+    /// Length of the fade, in seconds.
+    pub length: f32,
+}
+
+/// Lock track key: lock the player's input for a length of time.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineLock {
+    pub time: f32,
+    /// Length of the lock, in seconds.
+    pub length: f32,
+}
+
+/// Timeline definition, for cut-scenes and scripted sequences, from a "data/timeline/*.json" file.
+/// Every key has a time, in seconds from the start of the timeline.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimelineData {
+    /// Length of the whole timeline, in seconds.
+    pub length: f32,
+    /// Allow the user to skip the timeline with the cancel response.
+    #[serde(default = "TimelineData::skip_default")]
+    pub skip: bool,
+    #[serde(default)]
+    pub camera: Vec<TimelineCamera>,
+    #[serde(default)]
+    pub input: Vec<TimelineInput>,
+    #[serde(default)]
+    pub sound: Vec<TimelineSound>,
+    #[serde(default)]
+    pub subtitle: Vec<TimelineSubtitle>,
+    #[serde(default)]
+    pub fade: Vec<TimelineFade>,
+    #[serde(default)]
+    pub lock: Vec<TimelineLock>,
+}
+
+impl TimelineData {
+    /// Path to the timeline definition folder.
+    const PATH: &str = "data/timeline";
+
+    fn skip_default() -> bool {
+        true
+    }
+
+    /// Load a timeline definition by name.
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        Self::load(&Self::get_path(name))
+    }
+
+    /// Get the path to a timeline definition by name.
+    pub fn get_path(name: &str) -> String {
+        format!("{}/{name}.json", Self::PATH)
+    }
+}
+
+impl Definition for TimelineData {
+    fn validate(&self) -> Result<(), String> {
+        if self.length <= 0.0 {
+            return Err("Length must be positive.".to_string());
+        }
+
+        let time_list = self
+            .camera
+            .iter()
+            .map(|key| key.time)
+            .chain(self.input.iter().map(|key| key.time))
+            .chain(self.sound.iter().map(|key| key.time))
+            .chain(self.subtitle.iter().map(|key| key.time))
+            .chain(self.fade.iter().map(|key| key.time))
+            .chain(self.lock.iter().map(|key| key.time));
+
+        for time in time_list {
+            if !(0.0..self.length).contains(&time) {
+                return Err(format!(
+                    "Key time {time} is outside of the timeline length."
+                ));
+            }
+        }
+
+        if self.camera.iter().any(|key| key.blend < 0.0) {
+            return Err("Camera blend must not be negative.".to_string());
+        }
+
+        for key in &self.subtitle {
+            key.caption.validate()?;
+        }
+
+        if self.fade.iter().any(|key| key.length < 0.0) {
+            return Err("Fade length must not be negative.".to_string());
+        }
+
+        if self.lock.iter().any(|key| key.length <= 0.0) {
+            return Err("Lock length must be positive.".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+//================================================================
+
+/// A playing timeline. Keys are fired on the game tick, so a timeline plays the same way at any
+/// frame rate.
+pub struct Timeline {
+    pub name: String,
+    data: TimelineData,
+    /// Camera path for each camera key.
+    camera_list: Vec<CameraPath>,
+    /// Camera source of the last camera key fired.
+    camera: Option<CameraHandle>,
+    /// Time since the start of the timeline, in seconds.
+    time: f32,
+}
+
+impl Timeline {
+    /// Load a timeline, and every camera path it uses.
+    pub fn new(name: &str) -> anyhow::Result<Self> {
+        let data = TimelineData::new(name)?;
+        let camera_list = data
+            .camera
+            .iter()
+            .map(|key| CameraPath::new(&key.path))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: name.to_string(),
+            data,
+            camera_list,
+            camera: None,
+            time: 0.0,
+        })
+    }
+
+    /// Get the path of every sound and music file the timeline plays, and whether it is a music.
+    pub fn get_sound_list(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.data
+            .sound
+            .iter()
+            .map(|key| (key.path.as_str(), key.music))
+    }
+
+    /// Check if the user may skip the timeline.
+    pub fn is_skip(&self) -> bool {
+        self.data.skip
+    }
+
+    /// Check if the player's input is locked.
+    pub fn is_lock(&self) -> bool {
+        self.data
+            .lock
+            .iter()
+            .any(|key| self.time >= key.time && self.time < key.time + key.length)
+    }
+
+    /// Check if the timeline is over.
+    pub fn is_finish(&self) -> bool {
+        self.time >= self.data.length
+    }
+
+    /// Advance the timeline by a time step, and fire every key crossed.
+    pub fn tick(
+        &mut self,
+        app: &mut App,
+        context: &mut Context,
+        world: &mut World,
+        time: f32,
+    ) -> anyhow::Result<()> {
+        let wrl = world as *mut World;
+        let from = self.time;
+        let to = self.time + time;
+        let cross = |key: f32| key >= from && key < to;
+
+        self.time = to;
+
+        for (i, key) in self.data.camera.iter().enumerate() {
+            if cross(key.time) {
+                if let Some(camera) = self.camera.take() {
+                    world.scene.camera.detach(camera);
+                }
+
+                let camera = world.scene.camera.attach(
+                    CameraSource::Path {
+                        path: self.camera_list[i].clone(),
+                        time: 0.0,
+                    },
+                    CameraSystem::PRIORITY_PATH,
+                    key.blend,
+                );
+
+                self.camera = Some(camera);
+            }
+        }
+
+        for key in &self.data.input {
+            if cross(key.time)
+                && let Some(entity) = world.entity_list.get_mut(&key.entity)
+            {
+                entity.input(app, context, unsafe { &mut *wrl }, &key.input)?;
+            }
+        }
+
+        for key in &self.data.sound {
+            if cross(key.time) {
+                if key.music {
+                    Noise::music_play(&mut world.scene, &key.path, key.point)?;
+                } else {
+                    Noise::sound_play(&mut world.scene, app, &key.path, key.point)?;
+                }
+            }
+        }
+
+        for key in &self.data.subtitle {
+            if cross(key.time) {
+                world.scene.caption.push(key.caption.clone());
+            }
+        }
+
+        for key in &self.data.fade {
+            if cross(key.time) {
+                world.fade = Fade::new(key.from, key.to, key.length);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Skip to the end of the timeline. Every remaining entity input is still sent, and the fade is
+    /// set to where the timeline would leave it, so that the world ends up in the same state.
+    pub fn skip(
+        &mut self,
+        app: &mut App,
+        context: &mut Context,
+        world: &mut World,
+    ) -> anyhow::Result<()> {
+        let wrl = world as *mut World;
+
+        for key in &self.data.input {
+            if key.time >= self.time
+                && let Some(entity) = world.entity_list.get_mut(&key.entity)
+            {
+                entity.input(app, context, unsafe { &mut *wrl }, &key.input)?;
+            }
+        }
+
+        if let Some(key) = self
+            .data
+            .fade
+            .iter()
+            .filter(|key| key.time + key.length >= self.time)
+            .max_by(|a, b| a.time.total_cmp(&b.time))
+        {
+            world.fade = Fade::new(key.to, key.to, 0.0);
+        }
+
+        // the timeline's subtitles would no longer match anything on screen.
+        world.scene.caption.clear();
+
+        self.time = self.data.length;
+
+        Ok(())
+    }
+
+    /// Stop the timeline, and detach its camera source.
+    pub fn close(&mut self, world: &mut World) {
+        // a finished path has already been detached by the camera system, in which case the
+        // handle is no longer valid and detaching does nothing.
+        if let Some(camera) = self.camera.take() {
+            world.scene.camera.detach(camera);
+        }
+    }
+}
@@ -48,6 +48,11 @@
 * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
+use crate::debug::DebugCategory;
+
+//================================================================
+
+use hashbrown::HashSet;
 use raylib::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -81,8 +86,26 @@ pub struct User {
     pub video_brightness: f32,
     /// Screen contrast.
     pub video_contrast: f32,
+    /// Screen gamma.
+    #[serde(default = "User::default_gamma")]
+    pub video_gamma: f32,
+    /// Color-blind filter.
+    #[serde(default)]
+    pub video_color_blind: ColorBlindKind,
+    /// Correct the color-blind filter, rather than simulate it.
+    #[serde(default)]
+    pub video_color_blind_correct: bool,
     /// Cross-hair.
     pub video_cross: bool,
+    /// Subtitles for speech.
+    #[serde(default = "User::default_subtitle")]
+    pub video_subtitle: bool,
+    /// Captions for non-speech sound effects.
+    #[serde(default)]
+    pub video_caption: bool,
+    /// Shadow quality. Applied on the next level load.
+    #[serde(default)]
+    pub video_shadow: ShadowQuality,
     /// Sound volume.
     pub audio_sound: f32,
     /// Music volume.
@@ -103,16 +126,85 @@ pub struct User {
     pub input_pull: Input,
     /// Info key.
     pub input_info: Input,
+    /// Screenshot key.
+    #[serde(default = "User::default_screenshot")]
+    pub input_screenshot: Input,
     /// Mouse sensitivity.
     pub input_mouse_scale: f32,
     /// Debug user data.
     #[serde(skip)]
     pub debug: Debug,
+    /// Fixed user data, which is never read from or written to file.
+    #[serde(skip)]
+    fixed: bool,
 }
 
 impl User {
     /// Path to user file.
     const PATH_FILE: &'static str = "user.json";
+
+    /// Default screen gamma, for user files without one.
+    fn default_gamma() -> f32 {
+        1.0
+    }
+
+    /// Default subtitle state, for user files without one.
+    fn default_subtitle() -> bool {
+        true
+    }
+
+    /// Default screenshot key, for user files without one.
+    fn default_screenshot() -> Input {
+        Input::new_board(KeyboardKey::KEY_F12)
+    }
+
+    /// Create fixed user data with every default setting, for off-screen captures, so that the
+    /// local user file can not change the result. It is never written to file.
+    pub fn new_fixed() -> Self {
+        let mut user = Self::new_base();
+        user.developer = false;
+        user.fixed = true;
+        user
+    }
+
+    /// Create user data with every default setting.
+    fn new_base() -> Self {
+        Self {
+            developer: true,
+            tutorial: true,
+            video_glyph: GlyphKind::PlayStation,
+            video_idiom: IdiomKind::English,
+            video_full: false,
+            video_field: 90.0,
+            video_shake: 1.0,
+            video_scale: 1.0,
+            video_tilt: 1.0,
+            video_rate: 60.0,
+            video_brightness: 1.0,
+            video_contrast: 1.0,
+            video_gamma: Self::default_gamma(),
+            video_color_blind: ColorBlindKind::default(),
+            video_color_blind_correct: false,
+            video_cross: true,
+            video_subtitle: Self::default_subtitle(),
+            video_caption: false,
+            video_shadow: ShadowQuality::default(),
+            audio_sound: 1.0,
+            audio_music: 1.0,
+            input_move_x_a: Input::new_board(KeyboardKey::KEY_W),
+            input_move_x_b: Input::new_board(KeyboardKey::KEY_S),
+            input_move_z_a: Input::new_board(KeyboardKey::KEY_A),
+            input_move_z_b: Input::new_board(KeyboardKey::KEY_D),
+            input_jump: Input::new_board(KeyboardKey::KEY_SPACE),
+            input_push: Input::new_mouse(MouseButton::MOUSE_BUTTON_LEFT),
+            input_pull: Input::new_mouse(MouseButton::MOUSE_BUTTON_RIGHT),
+            input_info: Input::new_board(KeyboardKey::KEY_TAB),
+            input_screenshot: Self::default_screenshot(),
+            input_mouse_scale: 1.0,
+            debug: Default::default(),
+            fixed: false,
+        }
+    }
 }
 
 impl Default for User {
@@ -123,39 +215,17 @@ impl Default for User {
         {
             data
         } else {
-            Self {
-                developer: true,
-                tutorial: true,
-                video_glyph: GlyphKind::PlayStation,
-                video_idiom: IdiomKind::English,
-                video_full: false,
-                video_field: 90.0,
-                video_shake: 1.0,
-                video_scale: 1.0,
-                video_tilt: 1.0,
-                video_rate: 60.0,
-                video_brightness: 1.0,
-                video_contrast: 1.0,
-                video_cross: true,
-                audio_sound: 1.0,
-                audio_music: 1.0,
-                input_move_x_a: Input::new_board(KeyboardKey::KEY_W),
-                input_move_x_b: Input::new_board(KeyboardKey::KEY_S),
-                input_move_z_a: Input::new_board(KeyboardKey::KEY_A),
-                input_move_z_b: Input::new_board(KeyboardKey::KEY_D),
-                input_jump: Input::new_board(KeyboardKey::KEY_SPACE),
-                input_push: Input::new_mouse(MouseButton::MOUSE_BUTTON_LEFT),
-                input_pull: Input::new_mouse(MouseButton::MOUSE_BUTTON_RIGHT),
-                input_info: Input::new_board(KeyboardKey::KEY_TAB),
-                input_mouse_scale: 1.0,
-                debug: Default::default(),
-            }
+            Self::new_base()
         }
     }
 }
 
 impl Drop for User {
     fn drop(&mut self) {
+        if self.fixed {
+            return;
+        }
+
         // Write user data to file.
         let data = serde_json::to_string_pretty(self).unwrap();
         std::fs::write(Self::PATH_FILE, data).unwrap();
@@ -538,6 +608,23 @@ pub enum IdiomKind {
     Spanish,
 }
 
+impl IdiomKind {
+    pub fn folder_name(&self) -> &str {
+        match self {
+            IdiomKind::English => "english",
+            IdiomKind::Spanish => "spanish",
+        }
+    }
+
+    /// Every letter and mark in the idiom's alphabet that is not in printable ASCII.
+    pub fn alphabet(&self) -> &str {
+        match self {
+            IdiomKind::English => "",
+            IdiomKind::Spanish => "áéíóúüñÁÉÍÓÚÜÑ¿¡",
+        }
+    }
+}
+
 impl Display for IdiomKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
@@ -551,15 +638,110 @@ impl Display for IdiomKind {
 
 //================================================================
 
+/// Shadow quality preference.
+#[derive(Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum ShadowQuality {
+    #[default]
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl ShadowQuality {
+    /// Get the shadow atlas size, in pixels.
+    pub fn size(&self) -> i32 {
+        match self {
+            Self::Off => 0,
+            Self::Low => 1024,
+            Self::Medium => 2048,
+            Self::High => 4096,
+        }
+    }
+
+    /// Get the maximum shadow atlas tile count used per frame. A directional light uses one tile,
+    /// a point light uses six, and the atlas has sixteen.
+    pub fn budget(&self) -> usize {
+        match self {
+            Self::Off => 0,
+            Self::Low => 6,
+            Self::Medium => 12,
+            Self::High => 16,
+        }
+    }
+
+    /// Get the PCF filter radius, in texels.
+    pub fn filter(&self) -> i32 {
+        match self {
+            Self::Off => 0,
+            Self::Low => 0,
+            Self::Medium => 1,
+            Self::High => 2,
+        }
+    }
+}
+
+impl Display for ShadowQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::Off => "Off",
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+        };
+
+        f.write_str(string)
+    }
+}
+
+/// Color-blind filter preference.
+#[derive(Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum ColorBlindKind {
+    #[default]
+    Off,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Display for ColorBlindKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            Self::Off => "Off",
+            Self::Protanopia => "Protanopia",
+            Self::Deuteranopia => "Deuteranopia",
+            Self::Tritanopia => "Tritanopia",
+        };
+
+        f.write_str(string)
+    }
+}
+
 /// Debug user data.
 #[derive(Default)]
 pub struct Debug {
-    /// Draw the world physical simulation.
-    pub draw_physical: bool,
-    /// Draw point entity.
-    pub draw_entity: bool,
+    /// Enabled debug draw category list.
+    pub draw_category: HashSet<DebugCategory>,
     /// Draw the frame rate.
     pub draw_frame_rate: bool,
+    /// Draw the render statistic overlay.
+    pub draw_stat: bool,
     /// Draw the light edit menu.
     pub draw_light_edit: bool,
+    /// Skip the post-process stack.
+    pub post_skip: bool,
+}
+
+impl Debug {
+    /// Check if a debug draw category is enabled.
+    pub fn has_category(&self, category: DebugCategory) -> bool {
+        self.draw_category.contains(&category)
+    }
+
+    /// Toggle a debug draw category.
+    pub fn toggle_category(&mut self, category: DebugCategory) {
+        if !self.draw_category.remove(&category) {
+            self.draw_category.insert(category);
+        }
+    }
 }
@@ -49,7 +49,11 @@
 */
 
 use crate::app::*;
+use crate::camera::*;
+use crate::caption::*;
+use crate::debug::DebugCategory;
 use crate::helper::*;
+use crate::idiom::*;
 use crate::scene::*;
 use crate::user::*;
 
@@ -75,12 +79,27 @@ pub struct View<'a> {
     view: Option<(Rectangle, f32)>,
     time: f32,
     glyph_kind: GlyphKind,
+    /// Localized string look-up.
+    pub idiom: Idiom,
 }
 
 impl<'a> View<'a> {
     const BUTTON_SHAPE_Y: f32 = 32.0;
     const FONT_SCALE: f32 = 32.0;
     const FONT_SPACE: f32 = 1.0;
+    const FONT_LABEL: &str = "data/video/font_label.ttf";
+    const FONT_TITLE: &str = "data/video/font_title.ttf";
+    /// Fall-back font chain, in order, for any code point missing from the label font.
+    const FONT_FALLBACK: [&str; 2] = [
+        "data/video/font_fallback.ttf",
+        "data/video/font_fallback_symbol.ttf",
+    ];
+    /// Distance from the bottom of the screen to the bottom of the last caption line.
+    const CAPTION_MARGIN: f32 = 96.0;
+    /// Padding around the caption text, for the backing box.
+    const CAPTION_PAD: f32 = 8.0;
+    /// Space between each caption line.
+    const CAPTION_SPACE: f32 = 4.0;
 
     pub fn set_device(&mut self, device: Device) {
         self.device = device;
@@ -88,32 +107,8 @@ impl<'a> View<'a> {
 
     #[rustfmt::skip]
     pub fn initialize(&mut self, app: &App, context: &'a mut Context) -> anyhow::Result<()> {
-        let glyph_kind = ["play_station", "nintendo", "xbox"];
-        let glyph_list = [
-            "button_d.png",
-            "button_l.png",
-            "button_r.png",
-            "button_u.png",
-            "l_bumper.png",
-            "l_stick_click.png",
-            "l_stick.png",
-            "l_trigger.png",
-            "pad_d.png",
-            "pad_l.png",
-            "pad_r.png",
-            "pad_u.png",
-            "middle_r.png",
-            "r_bumper.png",
-            "r_stick_click.png",
-            "r_stick.png",
-            "r_trigger.png",
-            "middle_l.png",
-        ];
-
-        for kind in glyph_kind {
-            for list in glyph_list {
-                self.scene.asset.set_texture(context, &format!("data/video/glyph/{kind}/{list}"))?;
-            }
+        for kind in [GlyphKind::PlayStation, GlyphKind::Nintendo, GlyphKind::Xbox] {
+            self.scene.asset.set_atlas(context, &Self::glyph_atlas(kind.folder_name()))?;
         }
 
         self.layout = Some(Layout::Main);
@@ -124,14 +119,9 @@ impl<'a> View<'a> {
 
         self.scene.link()?;
 
-        self.scene.asset.set_texture(context, "data/video/glyph/mouse/button_l.png")?;
-        self.scene.asset.set_texture(context, "data/video/glyph/mouse/button_m.png")?;
-        self.scene.asset.set_texture(context, "data/video/glyph/mouse/button_r.png")?;
-        self.scene.asset.set_texture(context, "data/video/glyph/mouse/wheel_u.png")?;
-        self.scene.asset.set_texture(context, "data/video/glyph/mouse/wheel_d.png")?;
+        self.scene.asset.set_atlas(context, &Self::glyph_atlas("mouse"))?;
 
-        self.scene.asset.set_font(context, "data/video/font_label.ttf", 32)?;
-        self.scene.asset.set_font(context, "data/video/font_title.ttf", 56)?;
+        self.set_idiom(context, app.user.video_idiom)?;
         self.scene.asset.set_sound(context, "data/audio/hover.ogg", 0)?;
         self.scene.asset.set_sound(context, "data/audio/click.ogg", 0)?;
         self.scene.asset.set_sound(context, "data/audio/back.ogg", 0)?;
@@ -139,6 +129,39 @@ impl<'a> View<'a> {
         Ok(())
     }
 
+    /// Get the path to a glyph atlas.
+    fn glyph_atlas(kind: &str) -> String {
+        format!("data/video/glyph/{kind}.json")
+    }
+
+    /// Draw an atlas frame, at a given scale.
+    fn draw_sprite(
+        &self,
+        draw: &mut RaylibMode2D<'_, RaylibDrawHandle<'_>>,
+        atlas: &str,
+        frame: &str,
+        point: Vector2,
+        scale: f32,
+    ) -> anyhow::Result<()> {
+        let sprite = self.scene.asset.get_sprite(atlas, frame)?;
+
+        draw.draw_texture_pro(
+            sprite.texture,
+            sprite.shape,
+            Rectangle::new(
+                point.x,
+                point.y,
+                sprite.shape.width * scale,
+                sprite.shape.height * scale,
+            ),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+
+        Ok(())
+    }
+
     fn begin(&mut self) {
         self.point = Vector2::new(8.0, 8.0);
         self.index = usize::default();
@@ -149,15 +172,98 @@ impl<'a> View<'a> {
         self.device = self.device.poll_change(handle);
     }
 
-    pub fn font_label(&self) -> anyhow::Result<&Font> {
-        self.scene.asset.get_font("data/video/font_label.ttf")
+    /// Switch to an idiom, and load every font again for its code points if it changed. A string
+    /// table that could not be loaded is reported, rather than failing the frame.
+    pub fn set_idiom(&mut self, context: &mut Context, kind: IdiomKind) -> anyhow::Result<()> {
+        match self.idiom.set_idiom(kind) {
+            Ok(false) => return Ok(()),
+            Ok(true) => {}
+            Err(error) => self.logger.print_failure(&format!("Error: {error:?}")),
+        }
+
+        self.set_font(context)
     }
 
-    fn font_measure(font: &Font, text: &str) -> Vector2 {
-        font.measure_text(text, Self::FONT_SCALE, Self::FONT_SPACE)
+    /// Load the label font, the title font and every fall-back font, with every code point in use
+    /// by the active idiom's string and caption tables.
+    fn set_font(&mut self, context: &mut Context) -> anyhow::Result<()> {
+        let kind = self.idiom.get_kind();
+        // a caption table that could not be loaded is reported by the caption queue as well, and
+        // its code points are left out.
+        let caption = match CaptionTable::new(kind) {
+            Ok(caption) => caption,
+            Err(error) => {
+                self.logger.print_failure(&format!("Error: {error:?}"));
+                CaptionTable::default()
+            }
+        };
+        let code = self.idiom.get_code_point() + &caption.get_code_point();
+
+        self.scene
+            .asset
+            .set_font(context, Self::FONT_LABEL, 32, &code)?;
+        self.scene
+            .asset
+            .set_font(context, Self::FONT_TITLE, 56, &code)?;
+
+        // fall-back fonts are optional, only load those present.
+        for path in Self::FONT_FALLBACK {
+            if std::path::Path::new(path).is_file() {
+                self.scene.asset.set_font(context, path, 32, &code)?;
+            }
+        }
+
+        Ok(())
     }
 
-    fn font_measure_box(font: &Font, text: &str, shape: Rectangle) -> anyhow::Result<Rectangle> {
+    /// Get the label font, followed by every fall-back font.
+    pub fn font_label(&self) -> anyhow::Result<Vec<&AssetFont>> {
+        let mut list = vec![self.scene.asset.get_font(Self::FONT_LABEL)?];
+
+        for path in Self::FONT_FALLBACK {
+            if let Ok(font) = self.scene.asset.get_font(path) {
+                list.push(font);
+            }
+        }
+
+        Ok(list)
+    }
+
+    /// Pick the first font in a font chain with a glyph for a code point. If no font has one,
+    /// pick the first font, which will draw its fall-back glyph instead.
+    fn font_pick<'b>(font: &[&'b AssetFont], code: char) -> &'b AssetFont {
+        font.iter()
+            .find(|font| font.has_glyph(code))
+            .copied()
+            .unwrap_or(font[0])
+    }
+
+    /// Measure text, one code point at a time, so that multi-byte text and text drawn with more
+    /// than one font in the chain is measured the same way it is drawn.
+    fn font_measure(font: &[&AssetFont], text: &str) -> Vector2 {
+        let mut size = Vector2::zero();
+
+        for line in text.split('\n') {
+            let count = line.chars().count();
+            let width: f32 = line
+                .chars()
+                .map(|code| Self::font_pick(font, code).get_advance(code, Self::FONT_SCALE))
+                .sum();
+
+            size.x = size
+                .x
+                .max(width + Self::FONT_SPACE * count.saturating_sub(1) as f32);
+            size.y += Self::FONT_SCALE;
+        }
+
+        size
+    }
+
+    fn font_measure_box(
+        font: &[&AssetFont],
+        text: &str,
+        shape: Rectangle,
+    ) -> anyhow::Result<Rectangle> {
         let size = Self::font_measure(font, text);
         Ok(Rectangle::new(
             shape.x,
@@ -169,12 +275,60 @@ impl<'a> View<'a> {
 
     pub fn font_draw(
         draw: &mut RaylibDrawHandle<'_>,
-        font: &Font,
+        font: &[&AssetFont],
         text: &str,
         point: Vector2,
         color: Color,
     ) {
-        draw.draw_text_ex(font, text, point, Self::FONT_SCALE, Self::FONT_SPACE, color);
+        let mut cursor = point;
+
+        for code in text.chars() {
+            if code == '\n' {
+                cursor = Vector2::new(point.x, cursor.y + Self::FONT_SCALE);
+                continue;
+            }
+
+            let pick = Self::font_pick(font, code);
+
+            draw.draw_text_codepoint(&pick.font, code as i32, cursor, Self::FONT_SCALE, color);
+
+            cursor.x += pick.get_advance(code, Self::FONT_SCALE) + Self::FONT_SPACE;
+        }
+    }
+
+    /// Draw every caption on screen, centered at the bottom of the screen, one line each over a
+    /// backing box.
+    pub fn draw_caption(
+        &self,
+        draw: &mut RaylibMode2D<'_, RaylibDrawHandle<'_>>,
+        queue: &CaptionQueue,
+    ) -> anyhow::Result<()> {
+        let font = self.font_label()?;
+        let caption_list: Vec<&Caption> = queue.get_show_list().collect();
+        let mut y = draw.get_render_height() as f32 - Self::CAPTION_MARGIN;
+
+        // draw from the bottom line up.
+        for caption in caption_list.iter().rev() {
+            let text = caption.get_text();
+            let size = Self::font_measure(&font, &text);
+            let point = Vector2::new((draw.get_render_width() as f32 - size.x) * 0.5, y - size.y);
+
+            draw.draw_rectangle_rec(
+                Rectangle::new(
+                    point.x - Self::CAPTION_PAD,
+                    point.y - Self::CAPTION_PAD,
+                    size.x + Self::CAPTION_PAD * 2.0,
+                    size.y + Self::CAPTION_PAD * 2.0,
+                ),
+                Color::new(0, 0, 0, 160),
+            );
+
+            Self::font_draw(draw, &font, &text, point, caption.color);
+
+            y -= size.y + Self::CAPTION_PAD * 2.0 + Self::CAPTION_SPACE;
+        }
+
+        Ok(())
     }
 
     /// Begin a new UI frame.
@@ -202,6 +356,10 @@ impl<'a> View<'a> {
         context: &mut Context,
     ) -> anyhow::Result<()> {
         app.view.time += draw.get_frame_time();
+        app.view.scene.frame_time = draw.get_frame_time();
+
+        // only loads on idiom change, however the idiom was changed.
+        app.view.set_idiom(context, app.user.video_idiom)?;
 
         if app.view.device.escape(draw)
             && let Some(world) = &mut app.world
@@ -226,12 +384,16 @@ impl<'a> View<'a> {
 
                     app.view.scene.update(&*app_ref, context)?;
 
+                    for failure in app.view.scene.take_failure() {
+                        app.view.logger.print_failure(&failure);
+                    }
+
                     //app.view.scene.draw_3d(&mut *context, draw, |_| {
                     //    //
                     //    Ok(())
                     //})?;
 
-                    app.view.scene.draw_2d(&mut *context, draw, |draw| {
+                    app.view.scene.draw_2d(&*app_ref, context, draw, |draw| {
                         let app = &mut *app_ref;
                         let context = &mut *ctx_ref;
 
@@ -247,6 +409,11 @@ impl<'a> View<'a> {
 
                         Ok(())
                     })?;
+                } else if let Some(world) = &mut app.world
+                    && world.is_timeline_skip()
+                    && let Some((DeviceResponse::Cancel, true)) = app.view.device.response(draw)
+                {
+                    world.timeline_skip(&mut *app_ref, &mut *ctx_ref)?;
                 } else if app.view.device.escape(draw) {
                     Layout::set_layout(app, draw, Some(Layout::Main));
                     draw.enable_cursor();
@@ -271,58 +438,54 @@ impl<'a> View<'a> {
             InputKind::Board => {
                 View::font_draw(
                     draw,
-                    self.font_label()?,
+                    &self.font_label()?,
                     &format!("{}", input),
                     point,
                     Color::WHITE,
                 );
             }
             InputKind::Mouse => {
-                let texture = match input.to_mouse() {
-                    MouseButton::MOUSE_BUTTON_LEFT => "button_l.png",
-                    MouseButton::MOUSE_BUTTON_RIGHT => "button_r.png",
-                    MouseButton::MOUSE_BUTTON_MIDDLE => "button_m.png",
-                    MouseButton::MOUSE_BUTTON_SIDE => "button_l.png",
-                    MouseButton::MOUSE_BUTTON_EXTRA => "button_l.png",
-                    MouseButton::MOUSE_BUTTON_FORWARD => "button_l.png",
-                    MouseButton::MOUSE_BUTTON_BACK => "button_l.png",
+                let frame = match input.to_mouse() {
+                    MouseButton::MOUSE_BUTTON_LEFT => "button_l",
+                    MouseButton::MOUSE_BUTTON_RIGHT => "button_r",
+                    MouseButton::MOUSE_BUTTON_MIDDLE => "button_m",
+                    MouseButton::MOUSE_BUTTON_SIDE => "button_l",
+                    MouseButton::MOUSE_BUTTON_EXTRA => "button_l",
+                    MouseButton::MOUSE_BUTTON_FORWARD => "button_l",
+                    MouseButton::MOUSE_BUTTON_BACK => "button_l",
                 };
 
-                let texture = self
-                    .scene
-                    .asset
-                    .get_texture(&format!("data/video/glyph/mouse/{texture}"))?;
-
-                draw.draw_texture_ex(texture, point, 0.0, 0.35, Color::WHITE);
+                self.draw_sprite(draw, &Self::glyph_atlas("mouse"), frame, point, 0.35)?;
             }
             InputKind::Pad => {
-                let texture = match input.to_pad() {
-                    GamepadButton::GAMEPAD_BUTTON_UNKNOWN => "pad_u.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP => "pad_u.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT => "pad_r.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN => "pad_d.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT => "pad_l.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP => "button_u.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT => "button_r.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN => "button_d.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT => "button_l.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1 => "l_bumper.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2 => "l_trigger.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1 => "r_bumper.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2 => "r_trigger.png",
-                    GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT => "middle_l.png",
-                    GamepadButton::GAMEPAD_BUTTON_MIDDLE => "middle.png",
-                    GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT => "middle_r.png",
-                    GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB => "l_stick_click.png",
-                    GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB => "r_stick_click.png",
+                let frame = match input.to_pad() {
+                    GamepadButton::GAMEPAD_BUTTON_UNKNOWN => "pad_u",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP => "pad_u",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT => "pad_r",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN => "pad_d",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT => "pad_l",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP => "button_u",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT => "button_r",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN => "button_d",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT => "button_l",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1 => "l_bumper",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2 => "l_trigger",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1 => "r_bumper",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2 => "r_trigger",
+                    GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT => "middle_l",
+                    GamepadButton::GAMEPAD_BUTTON_MIDDLE => "middle",
+                    GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT => "middle_r",
+                    GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB => "l_stick_click",
+                    GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB => "r_stick_click",
                 };
 
-                let texture = self.scene.asset.get_texture(&format!(
-                    "data/video/glyph/{}/{texture}",
-                    self.glyph_kind.folder_name()
-                ))?;
-
-                draw.draw_texture_ex(texture, point, 0.0, 0.35, Color::WHITE);
+                self.draw_sprite(
+                    draw,
+                    &Self::glyph_atlas(self.glyph_kind.folder_name()),
+                    frame,
+                    point,
+                    0.35,
+                )?;
             }
         }
 
@@ -351,8 +514,9 @@ impl<'a> View<'a> {
         draw: &mut RaylibMode2D<'_, RaylibDrawHandle<'_>>,
         text: &str,
     ) -> anyhow::Result<Response> {
+        let text = &self.idiom.get(text);
         let size = Self::font_measure_box(
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Rectangle::new(self.point.x, self.point.y, 24.0, Self::BUTTON_SHAPE_Y),
         )?;
@@ -373,7 +537,7 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size, color.0);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             self.point + Vector2::new(4.0, -2.0),
             color.1,
@@ -384,7 +548,7 @@ impl<'a> View<'a> {
                 self,
                 draw,
                 Vector2::new(8.0, 0.0),
-                "Accept",
+                "glyph.accept",
                 DeviceResponse::Accept,
             )?;
         }
@@ -403,9 +567,10 @@ impl<'a> View<'a> {
         text: &str,
         value: &mut bool,
     ) -> anyhow::Result<Response> {
+        let text = &self.idiom.get(text);
         let keep = *value;
         let size = Self::font_measure_box(
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Rectangle::new(self.point.x, self.point.y, 24.0, Self::BUTTON_SHAPE_Y),
         )?;
@@ -419,7 +584,7 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size, Color::BLACK);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             self.point + Vector2::new(4.0, -2.0),
             Color::WHITE,
@@ -455,7 +620,7 @@ impl<'a> View<'a> {
                 self,
                 draw,
                 Vector2::new(8.0, 0.0),
-                "Modify",
+                "glyph.modify",
                 DeviceResponse::Accept,
             )?;
         }
@@ -481,9 +646,10 @@ impl<'a> View<'a> {
         bound: (f32, f32),
         step: f32,
     ) -> anyhow::Result<Response> {
+        let text = &self.idiom.get(text);
         let keep = value.clone();
         let size = Self::font_measure_box(
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Rectangle::new(self.point.x, self.point.y, 24.0, Self::BUTTON_SHAPE_Y),
         )?;
@@ -497,7 +663,7 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size, Color::BLACK);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             self.point + Vector2::new(4.0, -2.0),
             Color::WHITE,
@@ -578,10 +744,10 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size_b, color.1);
         draw.draw_rectangle_rec(size_c, Color::BLACK);
         let text = &format!("{:.2}", value);
-        let measure = Self::font_measure(self.font_label()?, text);
+        let measure = Self::font_measure(&self.font_label()?, text);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Vector2::new(
                 size_c.x + size_c.width * 0.5 - measure.x * 0.5,
@@ -595,7 +761,7 @@ impl<'a> View<'a> {
                     self,
                     draw,
                     Vector2::new(8.0, 0.0),
-                    if response.focus { "" } else { "Modify" },
+                    if response.focus { "" } else { "glyph.modify" },
                     DeviceResponse::Accept,
                 )?;
 
@@ -609,7 +775,7 @@ impl<'a> View<'a> {
                     self,
                     draw,
                     Vector2::new(8.0 + push, 0.0),
-                    "Modify",
+                    "glyph.modify",
                     DeviceResponse::SideA,
                 )?;
             }
@@ -629,9 +795,10 @@ impl<'a> View<'a> {
         value: &mut T,
         bound: &[T],
     ) -> anyhow::Result<Response> {
+        let text = &self.idiom.get(text);
         let keep = *value;
         let size = Self::font_measure_box(
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Rectangle::new(self.point.x, self.point.y, 24.0, Self::BUTTON_SHAPE_Y),
         )?;
@@ -645,7 +812,7 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size, Color::BLACK);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             self.point + Vector2::new(4.0, -2.0),
             Color::WHITE,
@@ -712,11 +879,11 @@ impl<'a> View<'a> {
 
         draw.draw_rectangle_rec(size_a, color.0);
         draw.draw_rectangle_rec(size_b, color.0);
-        let text = &*value.to_string();
-        let measure = Self::font_measure(self.font_label()?, text);
+        let text = &*self.idiom.get_value(&*value);
+        let measure = Self::font_measure(&self.font_label()?, text);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Vector2::new(
                 size_b.x + size_b.width * 0.5 - measure.x * 0.5,
@@ -730,7 +897,7 @@ impl<'a> View<'a> {
                     self,
                     draw,
                     Vector2::new(8.0, 0.0),
-                    "Modify",
+                    "glyph.modify",
                     DeviceResponse::Accept,
                 )?;
             } else {
@@ -738,7 +905,7 @@ impl<'a> View<'a> {
                     self,
                     draw,
                     Vector2::new(8.0, 0.0),
-                    "Modify",
+                    "glyph.modify",
                     DeviceResponse::SideA,
                 )?;
             }
@@ -757,8 +924,9 @@ impl<'a> View<'a> {
         text: &str,
         value: &mut Input,
     ) -> anyhow::Result<Response> {
+        let text = &self.idiom.get(text);
         let size = Self::font_measure_box(
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Rectangle::new(self.point.x, self.point.y, 24.0, Self::BUTTON_SHAPE_Y),
         )?;
@@ -772,7 +940,7 @@ impl<'a> View<'a> {
         draw.draw_rectangle_rec(size, Color::BLACK);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             self.point + Vector2::new(4.0, -2.0),
             Color::WHITE,
@@ -833,17 +1001,17 @@ impl<'a> View<'a> {
                 self,
                 draw,
                 Vector2::new(8.0, 0.0),
-                "Modify",
+                "glyph.modify",
                 DeviceResponse::Accept,
             )?;
         }
 
         /*
         let text = &*value.to_string();
-        let measure = Self::font_measure(self.font_label()?, text);
+        let measure = Self::font_measure(&self.font_label()?, text);
         Self::font_draw(
             draw,
-            self.font_label()?,
+            &self.font_label()?,
             text,
             Vector2::new(
                 size_b.x + size_b.width * 0.5 - measure.x * 0.5,
@@ -1176,7 +1344,7 @@ impl Logger {
         for (i, line) in self.history.iter().rev().enumerate() {
             View::font_draw(
                 draw,
-                font,
+                &font,
                 &line.text,
                 Vector2::new(
                     8.0,
@@ -1202,17 +1370,17 @@ impl Logger {
 
             View::font_draw(
                 draw,
-                font,
+                &font,
                 &line.name,
                 point + Vector2::new(8.0, 0.0),
                 Color::WHITE,
             );
 
-            let measure = View::font_measure(font, &line.info);
+            let measure = View::font_measure(&font, &line.info);
 
             View::font_draw(
                 draw,
-                font,
+                &font,
                 &line.info,
                 point + Vector2::new(scale.x - measure.x - 8.0, 0.0),
                 Color::new(192, 192, 192, 255),
@@ -1221,7 +1389,7 @@ impl Logger {
 
         View::font_draw(
             draw,
-            font,
+            &font,
             &self.buffer,
             Vector2::new(8.0, scale.y - 40.0),
             Color::WHITE,
@@ -1251,7 +1419,7 @@ impl Logger {
 
             View::font_draw(
                 draw,
-                font,
+                &font,
                 &line.text,
                 Vector2::new(8.0, 8.0 + 32.0 * i as f32),
                 line.kind.color(),
@@ -1358,13 +1526,32 @@ impl Logger {
     }
 
     fn draw_physical(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
-        app.user.debug.draw_physical = !app.user.debug.draw_physical;
+        app.user.debug.toggle_category(DebugCategory::Physical);
 
         Ok(())
     }
 
     fn draw_entity(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
-        app.user.debug.draw_entity = !app.user.debug.draw_entity;
+        app.user.debug.toggle_category(DebugCategory::Entity);
+
+        Ok(())
+    }
+
+    fn draw_debug(app: &mut App, _: &mut Context, token: Vec<&str>) -> anyhow::Result<()> {
+        if let Some(token) = token.get(1)
+            && let Some(category) = DebugCategory::from_name(token)
+        {
+            app.user.debug.toggle_category(category);
+        } else {
+            let list: Vec<String> = DebugCategory::LIST
+                .iter()
+                .map(|category| category.to_string().to_lowercase())
+                .collect();
+
+            app.view
+                .logger
+                .print_failure(&format!("Usage: draw_debug {{{}}}", list.join(" | ")));
+        }
 
         Ok(())
     }
@@ -1375,11 +1562,120 @@ impl Logger {
         Ok(())
     }
 
+    fn draw_stat(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        app.user.debug.draw_stat = !app.user.debug.draw_stat;
+
+        Ok(())
+    }
+
     fn draw_light_edit(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
         app.user.debug.draw_light_edit = !app.user.debug.draw_light_edit;
 
         Ok(())
     }
+
+    fn post_skip(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        app.user.debug.post_skip = !app.user.debug.post_skip;
+
+        Ok(())
+    }
+
+    fn screenshot(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        app.record.screenshot();
+
+        Ok(())
+    }
+
+    fn record(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        let path = app.record.toggle_sequence()?;
+
+        if app.record.is_sequence() {
+            app.view
+                .logger
+                .print_command(&format!("Recording frame sequence to \"{path}\"."));
+        } else {
+            app.view
+                .logger
+                .print_command(&format!("Frame sequence written to \"{path}\"."));
+        }
+
+        Ok(())
+    }
+
+    fn free_camera(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        if let Some(world) = &mut app.world {
+            world.scene.camera.toggle_free();
+        } else {
+            app.view
+                .logger
+                .print_failure("No world to use a free camera in.");
+        }
+
+        Ok(())
+    }
+
+    fn camera_path(app: &mut App, _: &mut Context, token: Vec<&str>) -> anyhow::Result<()> {
+        let Some(token) = token.get(1) else {
+            app.view
+                .logger
+                .print_failure("Usage: camera_path {camera path name}");
+            return Ok(());
+        };
+
+        if let Some(world) = &mut app.world {
+            world.scene.camera.attach(
+                CameraSource::Path {
+                    path: CameraPath::new(token)?,
+                    time: 0.0,
+                },
+                CameraSystem::PRIORITY_PATH,
+                CameraSystem::BLEND,
+            );
+        } else {
+            app.view
+                .logger
+                .print_failure("No world to play a camera path in.");
+        }
+
+        Ok(())
+    }
+
+    fn idiom_missing(app: &mut App, _: &mut Context, _: Vec<&str>) -> anyhow::Result<()> {
+        let list = app.view.idiom.get_missing();
+        let text = idiom!(
+            app.view.idiom,
+            "logger.idiom_missing",
+            count = list.len(),
+            idiom = app.view.idiom.get_kind(),
+        );
+
+        app.view.logger.print_command(&text);
+
+        for key in list {
+            app.view.logger.print_warning(&key);
+        }
+
+        Ok(())
+    }
+
+    fn timeline(app: &mut App, context: &mut Context, token: Vec<&str>) -> anyhow::Result<()> {
+        let Some(token) = token.get(1) else {
+            app.view
+                .logger
+                .print_failure("Usage: timeline {timeline name}");
+            return Ok(());
+        };
+
+        if let Some(world) = &mut app.world {
+            world.timeline_play(context, token)?;
+        } else {
+            app.view
+                .logger
+                .print_failure("No world to play a timeline in.");
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Logger {
@@ -1394,8 +1690,17 @@ impl Default for Logger {
         Self::register_command(&mut command, "new_level",       "Load a new level.",                   Self::new_level);
         Self::register_command(&mut command, "draw_physical",   "Draw the world physical simulation.", Self::draw_physical);
         Self::register_command(&mut command, "draw_entity",     "Draw point entity.",                  Self::draw_entity);
+        Self::register_command(&mut command, "draw_debug",      "Toggle a debug draw category.",       Self::draw_debug);
         Self::register_command(&mut command, "draw_frame_rate", "Draw the frame rate.",                Self::draw_frame_rate);
+        Self::register_command(&mut command, "draw_stat",       "Draw the render statistic overlay.",  Self::draw_stat);
         Self::register_command(&mut command, "draw_light_edit", "Draw the light edit menu.",           Self::draw_light_edit);
+        Self::register_command(&mut command, "post_skip",       "Skip the post-process stack.",        Self::post_skip);
+        Self::register_command(&mut command, "screenshot",      "Save a screenshot.",                  Self::screenshot);
+        Self::register_command(&mut command, "record",          "Begin or end a frame sequence.",      Self::record);
+        Self::register_command(&mut command, "free_camera",     "Toggle the debug free camera.",       Self::free_camera);
+        Self::register_command(&mut command, "camera_path",     "Play a camera path.",                 Self::camera_path);
+        Self::register_command(&mut command, "timeline",        "Play a timeline.",                    Self::timeline);
+        Self::register_command(&mut command, "idiom_missing",   "List keys missing from the idiom.",   Self::idiom_missing);
 
         Self {
             active: false,
@@ -1449,14 +1754,14 @@ impl Layout {
         View::draw(app, draw, |app, draw| {
             app.view.point = Self::INITIAL_POINT;
 
-            if app.view.button(draw, "begin")?.accept() {
+            if app.view.button(draw, "main.begin")?.accept() {
                 app.new_world(context)?;
             };
-            if app.view.button(draw, "setup")?.accept() {
+            if app.view.button(draw, "main.setup")?.accept() {
                 layout = Some(Some(Self::Setup));
             };
 
-            if app.view.button(draw, "close")?.accept() {
+            if app.view.button(draw, "main.close")?.accept() {
                 if app.world.is_some() {
                     layout = Some(None);
                 } else {
@@ -1523,35 +1828,40 @@ impl Layout {
 
             app.view
                 .scroll(draw, Vector2::new(768.0, y), |window, draw| {
-                    window.toggle(draw, "play tutorial", &mut app.user.tutorial)?;
+                    window.toggle(draw, "setup.play_tutorial", &mut app.user.tutorial)?;
                     window.switch(
                         draw,
-                        "glyph kind",
+                        "setup.glyph_kind",
                         &mut app.user.video_glyph,
                         &[GlyphKind::PlayStation, GlyphKind::Xbox, GlyphKind::Nintendo],
                     )?;
-                    window.switch(
-                        draw,
-                        "idiom kind",
-                        &mut app.user.video_idiom,
-                        &[IdiomKind::English, IdiomKind::Spanish],
-                    )?;
                     if window
-                        .toggle(draw, "screen full", &mut app.user.video_full)?
+                        .switch(
+                            draw,
+                            "setup.idiom_kind",
+                            &mut app.user.video_idiom,
+                            &[IdiomKind::English, IdiomKind::Spanish],
+                        )?
+                        .change
+                    {
+                        window.set_idiom(context, app.user.video_idiom)?;
+                    }
+                    if window
+                        .toggle(draw, "setup.screen_full", &mut app.user.video_full)?
                         .accept()
                     {
                         context.apply_user(&app.user);
                     }
                     window.slider(
                         draw,
-                        "screen field",
+                        "setup.screen_field",
                         &mut app.user.video_field,
                         (60.0, 120.0),
                         1.0,
                     )?;
                     window.slider(
                         draw,
-                        "screen shake",
+                        "setup.screen_shake",
                         &mut app.user.video_shake,
                         (0.0, 2.0),
                         0.1,
@@ -1559,7 +1869,7 @@ impl Layout {
                     if window
                         .slider(
                             draw,
-                            "screen scale",
+                            "setup.screen_scale",
                             &mut app.user.video_scale,
                             (0.1, 1.0),
                             0.05,
@@ -1578,7 +1888,55 @@ impl Layout {
                     }
                     window.slider(
                         draw,
-                        "screen tilt",
+                        "setup.screen_brightness",
+                        &mut app.user.video_brightness,
+                        (0.5, 1.5),
+                        0.05,
+                    )?;
+                    window.slider(
+                        draw,
+                        "setup.screen_contrast",
+                        &mut app.user.video_contrast,
+                        (0.5, 1.5),
+                        0.05,
+                    )?;
+                    window.slider(
+                        draw,
+                        "setup.screen_gamma",
+                        &mut app.user.video_gamma,
+                        (0.5, 2.5),
+                        0.1,
+                    )?;
+                    window.switch(
+                        draw,
+                        "setup.color_blind",
+                        &mut app.user.video_color_blind,
+                        &[
+                            ColorBlindKind::Off,
+                            ColorBlindKind::Protanopia,
+                            ColorBlindKind::Deuteranopia,
+                            ColorBlindKind::Tritanopia,
+                        ],
+                    )?;
+                    window.toggle(
+                        draw,
+                        "setup.color_blind_correct",
+                        &mut app.user.video_color_blind_correct,
+                    )?;
+                    window.switch(
+                        draw,
+                        "setup.shadow_quality",
+                        &mut app.user.video_shadow,
+                        &[
+                            ShadowQuality::Off,
+                            ShadowQuality::Low,
+                            ShadowQuality::Medium,
+                            ShadowQuality::High,
+                        ],
+                    )?;
+                    window.slider(
+                        draw,
+                        "setup.screen_tilt",
                         &mut app.user.video_tilt,
                         (0.0, 2.0),
                         0.1,
@@ -1586,7 +1944,7 @@ impl Layout {
                     if window
                         .slider(
                             draw,
-                            "screen rate",
+                            "setup.screen_rate",
                             &mut app.user.video_rate,
                             (30.0, 600.0),
                             1.0,
@@ -1597,29 +1955,32 @@ impl Layout {
                     }
                     window.slider(
                         draw,
-                        "sound volume",
+                        "setup.sound_volume",
                         &mut app.user.audio_sound,
                         (0.0, 1.0),
                         0.1,
                     )?;
                     window.slider(
                         draw,
-                        "music volume",
+                        "setup.music_volume",
                         &mut app.user.audio_music,
                         (0.0, 1.0),
                         0.1,
                     )?;
-
-                    window.action(draw, "move x+", &mut app.user.input_move_x_a)?;
-                    window.action(draw, "move x-", &mut app.user.input_move_x_b)?;
-                    window.action(draw, "move z+", &mut app.user.input_move_z_a)?;
-                    window.action(draw, "move z-", &mut app.user.input_move_z_b)?;
-                    window.action(draw, "jump", &mut app.user.input_jump)?;
-                    window.action(draw, "push", &mut app.user.input_push)?;
-                    window.action(draw, "pull", &mut app.user.input_pull)?;
+                    window.toggle(draw, "setup.subtitle", &mut app.user.video_subtitle)?;
+                    window.toggle(draw, "setup.caption", &mut app.user.video_caption)?;
+
+                    window.action(draw, "setup.move_x_a", &mut app.user.input_move_x_a)?;
+                    window.action(draw, "setup.move_x_b", &mut app.user.input_move_x_b)?;
+                    window.action(draw, "setup.move_z_a", &mut app.user.input_move_z_a)?;
+                    window.action(draw, "setup.move_z_b", &mut app.user.input_move_z_b)?;
+                    window.action(draw, "setup.jump", &mut app.user.input_jump)?;
+                    window.action(draw, "setup.push", &mut app.user.input_push)?;
+                    window.action(draw, "setup.pull", &mut app.user.input_pull)?;
+                    window.action(draw, "setup.screenshot", &mut app.user.input_screenshot)?;
                     window.slider(
                         draw,
-                        "mouse speed",
+                        "setup.mouse_speed",
                         &mut app.user.input_mouse_scale,
                         (0.0, 2.0),
                         0.1,
@@ -1628,7 +1989,7 @@ impl Layout {
                     Ok(())
                 })?;
 
-            if app.view.button(draw, "return")?.accept() {
+            if app.view.button(draw, "setup.return")?.accept() {
                 layout = Some(Self::Main);
             };
 
@@ -1738,24 +2099,31 @@ impl Device {
         label: &str,
         device_response: DeviceResponse,
     ) -> anyhow::Result<()> {
+        let label = &if label.is_empty() {
+            String::new()
+        } else {
+            window.idiom.get(label)
+        };
         let point = Vector2::new(point.x, point.y + draw.get_screen_height() as f32 - 64.0);
 
-        let mut draw_call =
-            |window: &mut View, point: Vector2, texture: &str, label: &str| -> anyhow::Result<()> {
-                let texture = window.scene.asset.get_texture(texture)?;
+        let mut draw_call = |window: &mut View,
+                             point: Vector2,
+                             atlas: &str,
+                             frame: &str,
+                             label: &str|
+         -> anyhow::Result<()> {
+            window.draw_sprite(draw, &View::glyph_atlas(atlas), frame, point, 0.5)?;
 
-                draw.draw_texture_ex(texture, point, 0.0, 0.5, Color::WHITE);
-
-                View::font_draw(
-                    draw,
-                    window.font_label()?,
-                    label,
-                    point + Vector2::new(56.0, 8.0),
-                    Color::WHITE,
-                );
+            View::font_draw(
+                draw,
+                &window.font_label()?,
+                label,
+                point + Vector2::new(56.0, 8.0),
+                Color::WHITE,
+            );
 
-                Ok(())
-            };
+            Ok(())
+        };
 
         match window.device {
             Device::Board { .. } => {
@@ -1767,7 +2135,7 @@ impl Device {
 
                 View::font_draw(
                     draw,
-                    window.font_label()?,
+                    &window.font_label()?,
                     &format!("[{key}] {label}"),
                     point + Vector2::new(0.0, 8.0),
                     Color::WHITE,
@@ -1775,44 +2143,36 @@ impl Device {
             }
             Device::Mouse { .. } => match device_response {
                 DeviceResponse::Accept => {
-                    draw_call(window, point, "data/video/glyph/mouse/button_l.png", label)?;
+                    draw_call(window, point, "mouse", "button_l", label)?;
                 }
                 DeviceResponse::Cancel => {
-                    draw_call(window, point, "data/video/glyph/mouse/button_r.png", label)?;
+                    draw_call(window, point, "mouse", "button_r", label)?;
                 }
                 _ => {
-                    draw_call(window, point, "data/video/glyph/mouse/wheel_u.png", "")?;
+                    draw_call(window, point, "mouse", "wheel_u", "")?;
                     draw_call(
                         window,
                         point + Vector2::new(40.0, 0.0),
-                        "data/video/glyph/mouse/wheel_d.png",
+                        "mouse",
+                        "wheel_d",
                         label,
                     )?;
                 }
             },
             Device::Pad { .. } => match device_response {
                 DeviceResponse::Accept => {
-                    draw_call(
-                        window,
-                        point,
-                        "data/video/glyph/play_station/button_d.png",
-                        label,
-                    )?;
+                    draw_call(window, point, "play_station", "button_d", label)?;
                 }
                 DeviceResponse::Cancel => {
-                    draw_call(
-                        window,
-                        point,
-                        "data/video/glyph/play_station/button_r.png",
-                        label,
-                    )?;
+                    draw_call(window, point, "play_station", "button_r", label)?;
                 }
                 _ => {
-                    draw_call(window, point, "data/video/glyph/play_station/pad_l.png", "")?;
+                    draw_call(window, point, "play_station", "pad_l", "")?;
                     draw_call(
                         window,
                         point + Vector2::new(56.0, 0.0),
-                        "data/video/glyph/play_station/pad_r.png",
+                        "play_station",
+                        "pad_r",
                         label,
                     )?;
                 }
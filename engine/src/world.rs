@@ -49,8 +49,13 @@
 */
 
 use crate::app::*;
+use crate::debug::*;
 use crate::entity::implementation::*;
+use crate::environment::*;
+use crate::post::*;
 use crate::scene::*;
+use crate::stat::*;
+use crate::timeline::*;
 
 //================================================================
 
@@ -58,6 +63,7 @@ use hashbrown::HashMap;
 use rapier3d::prelude::*;
 use raylib::prelude::*;
 use serde::Deserialize;
+use std::time::Instant;
 
 //================================================================
 
@@ -70,6 +76,12 @@ pub struct World<'a> {
     entity_attach: Vec<Box<dyn Entity>>,
     pub scene: Scene<'a>,
     pub player: Option<usize>,
+    /// Custom animation events fired during the current tick, with the index of the owner entity.
+    pub animation_event: Vec<(usize, HashMap<String, serde_json::Value>)>,
+    /// Full-screen fade, drawn by the player.
+    pub fade: Fade,
+    /// Playing timeline, if any.
+    pub timeline: Option<Timeline>,
 }
 
 impl<'a> World<'a> {
@@ -93,7 +105,7 @@ impl<'a> World<'a> {
                 )?;
             }
 
-            world.fuse_level(level);
+            world.fuse_level(context, level)?;
         }
 
         let wrl = &mut world as *mut Self;
@@ -126,7 +138,7 @@ impl<'a> World<'a> {
                 )?;
             }
 
-            world.fuse_level(level);
+            world.fuse_level(context, level)?;
         }
 
         let wrl = &mut world as *mut Self;
@@ -151,14 +163,34 @@ impl<'a> World<'a> {
         let world = self as *mut Self;
         let pause = app.view.layout.is_some() || app.view.logger.active;
 
-        if !pause {
-            let frame_time = context.handle.get_frame_time().min(0.25);
+        let frame_time = app.record.get_frame_time(&context.handle);
+
+        self.scene.frame_time = if pause { 0.0 } else { frame_time };
 
+        if !pause {
             self.step += frame_time;
 
             while self.step >= Self::TIME_STEP {
+                self.scene
+                    .debug
+                    .tick(&app.user.debug.draw_category, Self::TIME_STEP);
+
+                let time = Instant::now();
+
                 self.scene.physical.tick();
 
+                Light::locate(&mut self.scene);
+
+                if self.scene.debug.has_category(DebugCategory::Physical) {
+                    self.scene.physical.draw(&mut self.scene.debug);
+                }
+
+                if self.scene.debug.has_category(DebugCategory::Path) {
+                    self.scene.camera.draw_debug(&mut self.scene.debug);
+                }
+
+                app.stat.add_time(StatTime::Physical, time);
+
                 // improve this API, please.
                 if let Ok(lock) = &self.scene.physical.collision_handler.collision_list.lock() {
                     for event in lock.iter() {
@@ -169,10 +201,23 @@ impl<'a> World<'a> {
                     }
                 }
 
+                let time = Instant::now();
+
+                self.fade.tick(Self::TIME_STEP);
+                self.timeline_tick(app, context)?;
+
                 for (_, entity) in &mut self.entity_list {
                     entity.tick(app, context, unsafe { &mut *world })?;
                 }
 
+                for (index, data) in std::mem::take(&mut self.animation_event) {
+                    if let Some(entity) = self.entity_list.get_mut(&index) {
+                        entity.animation_event(app, context, unsafe { &mut *world }, &data)?;
+                    }
+                }
+
+                app.stat.add_time(StatTime::Tick, time);
+
                 if !self.entity_attach.is_empty() {
                     //self.entity_list.append(&mut self.entity_attach);
                 }
@@ -187,7 +232,28 @@ impl<'a> World<'a> {
 
         self.scene.update(app, context)?;
 
+        self.draw(app, draw, context, pause)
+    }
+
+    /// Draw the world, with no simulation step.
+    pub fn draw(
+        &mut self,
+        app: &mut App,
+        draw: &mut RaylibDrawHandle<'_>,
+        context: &mut Context,
+        pause: bool,
+    ) -> anyhow::Result<()> {
+        let world = self as *mut Self;
+
+        let time = Instant::now();
+
         if !pause {
+            let frame_time = self.scene.frame_time;
+
+            if let Some(camera) = self.scene.camera.update(&app.user, draw, frame_time) {
+                self.scene.camera_3d = camera;
+            }
+
             self.scene.draw_3d(context, draw, |draw| {
                 for (_, entity) in &mut self.entity_list {
                     entity.draw_3d(app, draw, unsafe { &mut *world })?;
@@ -195,9 +261,17 @@ impl<'a> World<'a> {
 
                 Ok(())
             })?;
+
+            for failure in self.scene.take_failure() {
+                app.view.logger.print_failure(&failure);
+            }
         }
 
-        self.scene.draw_2d(context, draw, |draw| {
+        app.stat.add_time(StatTime::Draw3D, time);
+
+        let time = Instant::now();
+
+        self.scene.draw_2d(app, context, draw, |draw| {
             if !pause {
                 for (_, entity) in &mut self.entity_list {
                     entity.draw_2d(app, draw, unsafe { &mut *world })?;
@@ -207,6 +281,8 @@ impl<'a> World<'a> {
             Ok(())
         })?;
 
+        app.stat.add_time(StatTime::Draw2D, time);
+
         Ok(())
     }
 
@@ -216,7 +292,9 @@ impl<'a> World<'a> {
     ) -> anyhow::Result<Option<&Box<dyn Entity>>> {
         let collider = self.scene.physical.get_collider(collider)?;
 
+        // room geometry hangs off of the room rigid body, which is not an entity.
         if let Some(parent) = collider.parent()
+            && Some(parent) != self.scene.room_rigid
             && let Ok(rigid) = self.scene.physical.get_rigid(parent)
         {
             return Ok(self.entity_find(rigid.user_data as usize));
@@ -231,7 +309,9 @@ impl<'a> World<'a> {
     ) -> anyhow::Result<Option<&mut Box<dyn Entity>>> {
         let collider = self.scene.physical.get_collider(collider)?;
 
+        // room geometry hangs off of the room rigid body, which is not an entity.
         if let Some(parent) = collider.parent()
+            && Some(parent) != self.scene.room_rigid
             && let Ok(rigid) = self.scene.physical.get_rigid(parent)
         {
             return Ok(self.entity_find_mutable(rigid.user_data as usize));
@@ -284,24 +364,110 @@ impl<'a> World<'a> {
         }
     }
 
-    fn fuse_level(&mut self, level: Level) {
+    /// Play a timeline by name, from "data/timeline". Any playing timeline is stopped first.
+    pub fn timeline_play(&mut self, context: &mut Context, name: &str) -> anyhow::Result<()> {
+        let ctx = context as *const Context;
+        let timeline = Timeline::new(name)?;
+
+        for (path, music) in timeline.get_sound_list() {
+            if music {
+                self.scene.asset.set_music(unsafe { &*ctx }, path)?;
+            } else {
+                self.scene.asset.set_sound(unsafe { &*ctx }, path, 0)?;
+            }
+        }
+
+        if let Some(mut timeline) = self.timeline.take() {
+            timeline.close(self);
+        }
+
+        self.timeline = Some(timeline);
+
+        Ok(())
+    }
+
+    /// Skip the playing timeline, if it can be skipped.
+    pub fn timeline_skip(&mut self, app: &mut App, context: &mut Context) -> anyhow::Result<()> {
+        if let Some(mut timeline) = self.timeline.take() {
+            if !timeline.is_skip() {
+                self.timeline = Some(timeline);
+                return Ok(());
+            }
+
+            timeline.skip(app, context, self)?;
+            timeline.close(self);
+        }
+
+        Ok(())
+    }
+
+    /// Check if there is a playing timeline the user may skip.
+    pub fn is_timeline_skip(&self) -> bool {
+        self.timeline
+            .as_ref()
+            .is_some_and(|timeline| timeline.is_skip())
+    }
+
+    /// Check if the player's input is locked by a timeline.
+    pub fn is_input_lock(&self) -> bool {
+        self.timeline
+            .as_ref()
+            .is_some_and(|timeline| timeline.is_lock())
+    }
+
+    /// Advance the playing timeline by one game tick.
+    fn timeline_tick(&mut self, app: &mut App, context: &mut Context) -> anyhow::Result<()> {
+        // the timeline is taken out of the world while it plays, as its keys may change the world.
+        if let Some(mut timeline) = self.timeline.take() {
+            timeline.tick(app, context, self, Self::TIME_STEP)?;
+
+            if timeline.is_finish() {
+                timeline.close(self);
+            } else if self.timeline.is_none() {
+                self.timeline = Some(timeline);
+            } else {
+                // an entity input has started another timeline.
+                timeline.close(self);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn fuse_level(&mut self, context: &mut Context, level: Level) -> anyhow::Result<()> {
+        self.scene.post.setting = level.post;
+        self.scene.environment.set_level(
+            &mut self.scene.asset,
+            context,
+            level.environment,
+            &level.level,
+        )?;
+
         for entity in level.entity_list {
             self.entity_list.insert(self.entity_index, entity);
             self.entity_index += 1;
         }
+
+        Ok(())
     }
 }
 
 //================================================================
 
+/// A level file: every room model, and every entity.
 #[derive(Deserialize)]
-struct Level {
-    level: Vec<String>,
-    entity_list: Vec<Box<dyn Entity>>,
+pub struct Level {
+    /// Room model paths, relative to the level folder.
+    pub level: Vec<String>,
+    pub entity_list: Vec<Box<dyn Entity>>,
+    #[serde(default)]
+    pub post: PostSetting,
+    #[serde(default)]
+    pub environment: EnvironmentData,
 }
 
 impl Level {
-    fn new(path: &str) -> anyhow::Result<Self> {
+    pub fn new(path: &str) -> anyhow::Result<Self> {
         let file = std::fs::read_to_string(path)?;
         Ok(serde_json::from_str(&file)?)
     }